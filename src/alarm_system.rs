@@ -0,0 +1,113 @@
+use specs::prelude::*;
+use super::{Map, TileType, Position, Viewshed, gamelog::GameLog};
+use crate::{AiState, Brain, Fabricator, Faction, SecurityCamera};
+use crate::faction;
+use crate::initiative_system::GameClock;
+use crate::robot_ai_system::SEARCH_TURNS;
+use crate::spawner;
+use rltk::Point;
+
+// Turns the alarm keeps sounding after the last camera lost sight of the salvager
+pub const ALARM_DURATION : i32 = 30;
+
+pub struct AlarmSystem {}
+
+impl<'a> System<'a> for AlarmSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( Entities<'a>,
+                        WriteExpect<'a, Map>,
+                        ReadExpect<'a, Point>,
+                        ReadExpect<'a, Entity>,
+                        ReadExpect<'a, GameClock>,
+                        WriteExpect<'a, GameLog>,
+                        ReadStorage<'a, SecurityCamera>,
+                        ReadStorage<'a, Viewshed>,
+                        ReadStorage<'a, Faction>,
+                        WriteStorage<'a, Brain>,
+                        WriteStorage<'a, Fabricator> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, mut map, player_pos, player_entity, clock, mut log, cameras, viewsheds, factions, mut brains, mut fabricators) = data;
+        let player_faction = factions.get(*player_entity);
+
+        // Cameras that were shut down or reprogrammed no longer report to the deck
+        let spotted = (&entities, &cameras, &viewsheds).join()
+            .any(|(camera, _camera, viewshed)| faction::is_hostile(factions.get(camera), player_faction) && viewshed.visible_tiles.contains(&*player_pos));
+
+        if spotted {
+            if map.alarm == 0 {
+                log.entries.push("A security camera spots you, the deck alarm goes off!".to_string());
+            }
+            map.alarm = ALARM_DURATION;
+            map.alarm_spot = Some(*player_pos);
+
+            // Every hostile on the deck is told where the salvager was seen
+            for (entity, brain) in (&entities, &mut brains).join() {
+                if !faction::is_hostile(factions.get(entity), player_faction) { continue; }
+                if brain.state == AiState::Chase || brain.state == AiState::Flee { continue; }
+                brain.state = AiState::Investigate;
+                brain.last_seen = Some(*player_pos);
+                brain.search_turns = SEARCH_TURNS;
+            }
+        } else if clock.ticked && map.alarm > 0 {
+            map.alarm -= 1;
+            if map.alarm == 0 {
+                map.alarm_spot = None;
+                log.entries.push("The deck alarm falls silent.".to_string());
+            }
+        }
+
+        // Fabricators only build while the alarm sounds and the grid feeds them
+        if !clock.ticked || map.alarm == 0 || !map.power.fabrication { return; }
+        for (entity, fabricator) in (&entities, &mut fabricators).join() {
+            if !faction::is_hostile(factions.get(entity), player_faction) { continue; }
+            fabricator.progress += 1;
+        }
+    }
+}
+
+// Every fabricator that finished a build puts a new robot on a free tile next to it, called once game time has moved on
+pub fn fabricate_robots(ecs : &mut World) {
+    let mut builds : Vec<(i32, i32)> = Vec::new();
+    let depth;
+    let alarm_spot;
+    // Scope to keep the borrow checker happy
+    {
+        let map = ecs.fetch::<Map>();
+        let mut log = ecs.fetch_mut::<GameLog>();
+        let mut fabricators = ecs.write_storage::<Fabricator>();
+        let positions = ecs.read_storage::<Position>();
+        depth = map.depth;
+        alarm_spot = map.alarm_spot;
+
+        for (fabricator, pos) in (&mut fabricators, &positions).join() {
+            if fabricator.progress < fabricator.interval { continue; }
+            fabricator.progress = 0;
+
+            let exit = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)].iter()
+                .map(|(dx, dy)| (pos.x + dx, pos.y + dy))
+                .find(|(x, y)| {
+                    let idx = map.xy_idx(*x, *y);
+                    map.tiles[idx] == TileType::Floor && !map.blocked_tiles[idx] && !builds.contains(&(*x, *y))
+                });
+            if let Some(spot) = exit {
+                builds.push(spot);
+                if map.visible_tiles[map.xy_idx(pos.x, pos.y)] {
+                    log.entries.push("The fabricator spits out a new robot.".to_string());
+                }
+            }
+        }
+    }
+
+    for (x, y) in builds {
+        let robot = spawner::random_machine(ecs, x, y, depth);
+
+        // Reinforcements go straight for where the salvager was last spotted
+        let mut brains = ecs.write_storage::<Brain>();
+        if let (Some(spot), Some(brain)) = (alarm_spot, robot.and_then(|robot| brains.get_mut(robot))) {
+            brain.state = AiState::Investigate;
+            brain.last_seen = Some(spot);
+            brain.search_turns = SEARCH_TURNS;
+        }
+    }
+}
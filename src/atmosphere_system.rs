@@ -0,0 +1,96 @@
+use specs::prelude::*;
+use super::{Map, TileType};
+use crate::initiative_system::GameClock;
+use rltk::RandomNumberGenerator;
+use std::cmp::{max, min};
+
+pub const FULL_PRESSURE : i32 = 100;
+pub const BREATHABLE_PRESSURE : i32 = 50;
+const VENT_RATE : i32 = 25;
+const LIFE_SUPPORT_RATE : i32 = 10;
+const BREACH_CHANCE : i32 = 40;
+
+pub struct AtmosphereSystem {}
+
+impl<'a> System<'a> for AtmosphereSystem {
+    type SystemData = ( WriteExpect<'a, Map>,
+                        ReadExpect<'a, GameClock> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (mut map, clock) = data;
+
+        if !clock.ticked { return }
+
+        for compartment in compartments(&map).iter() {
+            let breached = compartment.iter().any(|idx| map.breaches[*idx]);
+            if breached {
+                // Air escapes through the hull breach over a few turns
+                for idx in compartment.iter() {
+                    map.atmosphere[*idx] = max(0, map.atmosphere[*idx] - VENT_RATE);
+                }
+            } else {
+                // Sealed sections share whatever air they hold
+                let total : i32 = compartment.iter().map(|idx| map.atmosphere[*idx]).sum();
+                let mut average = total / compartment.len() as i32;
+                if map.power.life_support {
+                    average = min(FULL_PRESSURE, average + LIFE_SUPPORT_RATE);
+                }
+                for idx in compartment.iter() {
+                    map.atmosphere[*idx] = average;
+                }
+            }
+        }
+    }
+}
+
+pub fn holds_air(map : &Map, idx : usize) -> bool {
+    !map.tiles[idx].is_solid()
+}
+
+// Groups all tiles that air can flow between. Walls and closed doors seal compartments off.
+pub fn compartments(map : &Map) -> Vec<Vec<usize>> {
+    let mut visited = vec![false; map.tiles.len()];
+    let mut result : Vec<Vec<usize>> = Vec::new();
+
+    for start in 0..map.tiles.len() {
+        if visited[start] || !holds_air(map, start) { continue; }
+
+        let mut compartment : Vec<usize> = Vec::new();
+        let mut open_list : Vec<usize> = vec![start];
+        visited[start] = true;
+        while let Some(idx) = open_list.pop() {
+            compartment.push(idx);
+            let x = idx as i32 % map.width;
+            let y = idx as i32 / map.width;
+            for (nx, ny) in [(x-1, y), (x+1, y), (x, y-1), (x, y+1)].iter() {
+                if *nx < 0 || *nx >= map.width || *ny < 0 || *ny >= map.height { continue; }
+                let neighbour = map.xy_idx(*nx, *ny);
+                if !visited[neighbour] && holds_air(map, neighbour) {
+                    visited[neighbour] = true;
+                    open_list.push(neighbour);
+                }
+            }
+        }
+        result.push(compartment);
+    }
+
+    result
+}
+
+// Fills every sealed compartment with air, except the ones that get a hull breach
+pub fn pressurize_compartments(map : &mut Map, rng : &mut RandomNumberGenerator) {
+    for compartment in compartments(map).iter() {
+        let floor_tiles : Vec<usize> = compartment.iter().filter(|idx| map.tiles[**idx] == TileType::Floor).cloned().collect();
+        if !floor_tiles.is_empty() && rng.roll_dice(1, 100) <= BREACH_CHANCE {
+            let breach = floor_tiles[rng.random_slice_index(&floor_tiles).unwrap()];
+            map.breaches[breach] = true;
+            for idx in compartment.iter() {
+                map.atmosphere[*idx] = 0;
+            }
+        } else {
+            for idx in compartment.iter() {
+                map.atmosphere[*idx] = FULL_PRESSURE;
+            }
+        }
+    }
+}
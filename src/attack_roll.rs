@@ -0,0 +1,44 @@
+use rltk::RandomNumberGenerator;
+
+const BASE_HIT_CHANCE : i32 = 80;
+const MIN_HIT_CHANCE : i32 = 5;
+const MAX_HIT_CHANCE : i32 = 95;
+const CRITICAL_CHANCE : i32 = 5;
+const GLANCING_BAND : i32 = 15;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum AttackRoll { Miss, Glancing, Hit, Critical }
+
+// Rolls d100 against the attacker's accuracy and the defender's evasion (both in percent).
+// The lowest rolls are critical hits, the rolls that only just made it are glancing blows.
+pub fn roll_attack(rng : &mut RandomNumberGenerator, accuracy : i32, evasion : i32) -> AttackRoll {
+    let hit_chance = i32::max(MIN_HIT_CHANCE, i32::min(MAX_HIT_CHANCE, BASE_HIT_CHANCE + accuracy - evasion));
+    let roll = rng.roll_dice(1, 100);
+    if roll > hit_chance {
+        AttackRoll::Miss
+    } else if roll <= CRITICAL_CHANCE {
+        AttackRoll::Critical
+    } else if roll > hit_chance - GLANCING_BAND {
+        AttackRoll::Glancing
+    } else {
+        AttackRoll::Hit
+    }
+}
+
+pub fn apply_roll(roll : AttackRoll, damage : i32) -> i32 {
+    match roll {
+        AttackRoll::Miss => 0,
+        AttackRoll::Glancing => damage / 2,
+        AttackRoll::Hit => damage,
+        AttackRoll::Critical => damage * 2
+    }
+}
+
+// How the log describes an attack that connected
+pub fn hit_verb(roll : AttackRoll) -> &'static str {
+    match roll {
+        AttackRoll::Critical => "critically hits",
+        AttackRoll::Glancing => "grazes",
+        _ => "hits"
+    }
+}
@@ -1,7 +1,9 @@
 use specs::prelude::*;
 use super::{Map,TileType,Position,Renderable};
 use rltk::{Point, Rltk, RGB};
-use crate::Target;
+use crate::{Target, Robot};
+use crate::atmosphere_system::BREATHABLE_PRESSURE;
+use crate::heat_system::FIRE_TEMPERATURE;
 
 pub fn get_screen_bounds(ecs: &World) -> (i32, i32, i32, i32) {
     let player_pos = ecs.fetch::<Point>();
@@ -53,12 +55,15 @@ pub fn render_camera(ecs: &World, ctx : &mut Rltk) {
     let entities = ecs.entities();
     let map = ecs.fetch::<Map>();
     let targets = ecs.read_storage::<Target>();
+    let robots = ecs.read_storage::<Robot>();
 
     let mut data = (&positions, &renderables, &entities).join().collect::<Vec<_>>();
     data.sort_by(|&a, &b| b.1.render_order.cmp(&a.1.render_order) );
     for (pos, render, entity) in data.iter() {
         let idx = map.xy_idx(pos.x, pos.y);
-        if map.visible_tiles[idx] {
+        // Powered internal sensors track every robot on the deck
+        let tracked = map.power.sensors && robots.get(*entity).is_some();
+        if map.visible_tiles[idx] || tracked {
             let entity_screen_x = pos.x - min_x;
             let entity_screen_y = pos.y - min_y;
             if entity_screen_x > 0 && entity_screen_x < map_width && entity_screen_y > 0 && entity_screen_y < map_height {
@@ -82,13 +87,53 @@ fn get_tile_glyph(idx: usize, map : &Map) -> (rltk::FontCharType, RGB, RGB) {
 
     match map.tiles[idx] {
         TileType::Floor => {
-            glyph = rltk::to_cp437('.');
-            fg = RGB::from_f32(0.0, 0.5, 0.5);
+            if map.heat_sources[idx] >= FIRE_TEMPERATURE {
+                glyph = rltk::to_cp437('▲');
+                fg = RGB::from_f32(1.0, 0.3, 0.);
+            } else if map.heat_sources[idx] > 0 {
+                // Engine block
+                glyph = rltk::to_cp437('■');
+                fg = RGB::from_f32(1.0, 0.6, 0.);
+            } else if map.breaches[idx] {
+                glyph = rltk::to_cp437('░');
+                fg = RGB::from_f32(0.5, 0.5, 0.5);
+            } else if map.atmosphere[idx] >= BREATHABLE_PRESSURE {
+                glyph = rltk::to_cp437('.');
+                fg = RGB::from_f32(0.0, 0.5, 0.5);
+            } else {
+                // Airless floor is drawn darker
+                glyph = rltk::to_cp437('.');
+                fg = RGB::from_f32(0.2, 0.2, 0.4);
+            }
         }
         TileType::Wall => {
             glyph = rltk::to_cp437('#');
             fg = RGB::from_f32(0., 1.0, 0.);
         }
+        TileType::LiftUp => {
+            glyph = rltk::to_cp437('<');
+            fg = RGB::from_f32(0., 1.0, 1.0);
+        }
+        TileType::LiftDown => {
+            glyph = rltk::to_cp437('>');
+            fg = RGB::from_f32(0., 1.0, 1.0);
+        }
+        TileType::DoorClosed => {
+            glyph = rltk::to_cp437('+');
+            fg = RGB::from_f32(1.0, 0.5, 0.);
+        }
+        TileType::DoorOpen => {
+            glyph = rltk::to_cp437('/');
+            fg = RGB::from_f32(1.0, 0.5, 0.);
+        }
+        TileType::Console => {
+            glyph = rltk::to_cp437('≡');
+            fg = RGB::from_f32(1.0, 1.0, 0.);
+        }
+        TileType::RepairStation => {
+            glyph = rltk::to_cp437('⌂');
+            fg = RGB::from_f32(0., 0.5, 1.0);
+        }
     }
 
     if !map.visible_tiles[idx] {
@@ -11,6 +11,13 @@ pub struct Position {
     pub y: i32,
 }
 
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct OtherDeckPosition {
+    pub x: i32,
+    pub y: i32,
+    pub depth: i32
+}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct Renderable {
     pub glyph: rltk::FontCharType,
@@ -40,12 +47,54 @@ pub struct Name {
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct BlocksTile {}
 
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum FactionId { Salvagers, Machines, Infected }
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Faction {
+    pub id : FactionId
+}
+
+// A reprogrammed robot that follows the salvager around
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Companion {}
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum AiState { Patrol, Investigate, Chase, Flee }
+
+// What a hostile is doing and what it remembers between turns
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Brain {
+    pub state : AiState,
+    pub patrol_route : Vec<rltk::Point>,
+    pub next_waypoint : usize,
+    pub last_seen : Option<rltk::Point>,
+    pub search_turns : i32
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Immobile {}
+
+// Names the loot table a robot's wreck is salvaged from
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct LootTable {
+    pub table : String
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Wreckage {
+    pub table : String
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct CombatStats {
     pub hp : Pool,
     pub energy : Pool,
     pub oxygen : Pool,
+    pub stamina : Pool,
+    pub heat : Pool,
     pub defense : i32,
+    pub evasion : i32,
     pub power : i32
 }
 
@@ -54,17 +103,51 @@ pub struct WantsToMelee {
     pub target : Entity
 }
 
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum DamageType { Kinetic, Energy, Thermal, Emp }
+
+impl DamageType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DamageType::Kinetic => "kinetic",
+            DamageType::Energy => "energy",
+            DamageType::Thermal => "thermal",
+            DamageType::Emp => "EMP"
+        }
+    }
+}
+
+// Percentage of each damage type that is shrugged off, negative values make it hurt more
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Resistances {
+    pub kinetic : i32,
+    pub energy : i32,
+    pub thermal : i32,
+    pub emp : i32
+}
+
+impl Resistances {
+    pub fn against(&self, damage_type : DamageType) -> i32 {
+        match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Energy => self.energy,
+            DamageType::Thermal => self.thermal,
+            DamageType::Emp => self.emp
+        }
+    }
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct SufferDamage {
-    pub amount : Vec<i32>
+    pub amount : Vec<(i32, DamageType)>
 }
 
 impl SufferDamage {
-    pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: i32) {
+    pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: i32, damage_type: DamageType) {
         if let Some(suffering) = store.get_mut(victim) {
-            suffering.amount.push(amount);
+            suffering.amount.push((amount, damage_type));
         } else {
-            let dmg = SufferDamage { amount : vec![amount] };
+            let dmg = SufferDamage { amount : vec![(amount, damage_type)] };
             store.insert(victim, dmg).expect("Unable to insert damage");
         }
     }
@@ -86,6 +169,55 @@ impl DrainEnergy {
     }
 }
 
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Exertion {
+    pub amount : Vec<i32>
+}
+
+impl Exertion {
+    pub fn new_exertion(store: &mut WriteStorage<Exertion>, victim: Entity, amount: i32) {
+        if let Some(exerting) = store.get_mut(victim) {
+            exerting.amount.push(amount);
+        } else {
+            let exertion = Exertion { amount : vec![amount] };
+            store.insert(victim, exertion).expect("Unable to insert exertion");
+        }
+    }
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct WantsToRest {}
+
+// Speed is how much game time an entity banks every tick, it gets to act while the bank isn't in debt
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Initiative {
+    pub speed : i32,
+    pub energy : i32
+}
+
+// How long the action an entity just took lasts, if it isn't a standard turn
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct TimeCost {
+    pub amount : i32
+}
+
+// Sounds an entity made this turn, each entry is how far it carries
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct MakesNoise {
+    pub loudness : Vec<i32>
+}
+
+impl MakesNoise {
+    pub fn new_noise(store: &mut WriteStorage<MakesNoise>, source: Entity, loudness: i32) {
+        if let Some(noise) = store.get_mut(source) {
+            noise.loudness.push(loudness);
+        } else {
+            let noise = MakesNoise { loudness : vec![loudness] };
+            store.insert(source, noise).expect("Unable to insert noise");
+        }
+    }
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct BreathOxygen {
     pub amount : Vec<i32>
@@ -142,7 +274,8 @@ pub struct Ranged {
 
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct InflictsDamage {
-    pub damage : i32
+    pub damage : i32,
+    pub damage_type : DamageType
 }
 
 #[derive(Component, Debug, ConvertSaveload, Clone)]
@@ -160,7 +293,10 @@ pub struct SerializeMe;
 // Special component that exists to help serialize the game data
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct SerializationHelper {
-    pub map : super::map::Map
+    pub map : super::map::Map,
+    pub seed : super::game_seed::GameSeed,
+    pub rng : rltk::RandomNumberGenerator,
+    pub decks : super::deck_master::DeckMaster
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -171,7 +307,20 @@ pub struct Pool {
 }
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
-pub enum EquipmentSlot { Weapon }
+pub enum EquipmentSlot { Weapon, Helmet, SuitBody, OxygenTank, BatteryPack, UtilityModule }
+
+impl EquipmentSlot {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipmentSlot::Weapon => "Weapon",
+            EquipmentSlot::Helmet => "Helmet",
+            EquipmentSlot::SuitBody => "Suit",
+            EquipmentSlot::OxygenTank => "Air tank",
+            EquipmentSlot::BatteryPack => "Battery",
+            EquipmentSlot::UtilityModule => "Utility"
+        }
+    }
+}
 
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Equippable {
@@ -186,15 +335,99 @@ pub struct Equipped {
 
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct MeleePowerBonus {
-    pub power : i32
+    pub power : i32,
+    pub accuracy : i32,
+    pub damage_type : DamageType
+}
+
+// The salvager's own stats before any suit equipment is added on top
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct BaseStats {
+    pub hp : i32,
+    pub energy : i32,
+    pub oxygen : i32,
+    pub stamina : i32,
+    pub defense : i32,
+    pub view_range : i32
+}
+
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct PoolBonus {
+    pub hp : i32,
+    pub energy : i32,
+    pub oxygen : i32
+}
+
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct DefenseBonus {
+    pub defense : i32
+}
+
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct ViewRangeBonus {
+    pub range : i32
+}
+
+// Chance in percent that a turn passes without using up suit oxygen
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct OxygenEfficiency {
+    pub percent : i32
+}
+
+// Pulls the suit's temperature back toward comfortable by this much every turn
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct ThermalRegulator {
+    pub amount : i32
+}
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum FiringPattern {
+    Single,
+    // Pellets hit everything in a cone from the shooter, radius tiles wide either side of the aimed-at tile
+    Spread { radius: i32 },
+    // Passes through everyone on the line until the weapon's range runs out
+    Beam,
+    // Needs a turn of aiming first, then shoots with extra accuracy
+    Scoped
 }
 
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct RangedWeapon {
     pub range: i32,
     pub damage: i32,
+    pub accuracy: i32,
+    pub damage_type: DamageType,
+    pub pattern: FiringPattern,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Aiming {
+    pub target : Entity
+}
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum AmmoType { EnergyCell, Slugs }
+
+// Shots left in a ranged weapon before it has to be reloaded
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct Magazine {
+    pub current: i32,
+    pub capacity: i32,
+    pub ammo: AmmoType,
 }
 
+impl Magazine {
+    pub fn describe(&self) -> String {
+        match self.ammo {
+            AmmoType::EnergyCell => format!("charge {}/{}", self.current, self.capacity),
+            AmmoType::Slugs => format!("rounds {}/{}", self.current, self.capacity)
+        }
+    }
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct AmmoPack {}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct WantsToRemoveItem {
     pub item : Entity
@@ -208,6 +441,29 @@ pub struct WantsToShoot {
     pub target : Entity
 }
 
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct WantsToHack {
+    pub target : Entity,
+    pub bonus : i32
+}
+
+// Lets the salvager break into an adjacent robot, the bonus adds to the chance of getting in
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct HackingTool {
+    pub bonus : i32
+}
+
+// Watches the deck and sounds the alarm when it spots the salvager
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct SecurityCamera {}
+
+// Builds a new robot every interval turns while the deck alarm is sounding
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Fabricator {
+    pub interval : i32,
+    pub progress : i32
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct ProvidesOxygen {
     pub oxygen_amount : i32
@@ -219,4 +475,12 @@ pub struct ProvidesEnergy {
 }
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
-pub struct ArtefactFromYendoria {}
\ No newline at end of file
+pub struct ArtefactFromYendoria {}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct CanOpenDoors {}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct PowerCore {
+    pub output : i32
+}
\ No newline at end of file
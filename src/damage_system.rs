@@ -1,17 +1,44 @@
 use specs::prelude::*;
 use super::{CombatStats, SufferDamage, Player, gamelog::GameLog, Name, RunState};
+use crate::{DamageType, Resistances, LootTable, Position, InBackpack};
+use crate::spawner;
 
 pub struct DamageSystem {}
 
 impl<'a> System<'a> for DamageSystem {
-    type SystemData = ( WriteStorage<'a, CombatStats>,
-                        WriteStorage<'a, SufferDamage> );
+    type SystemData = ( Entities<'a>,
+                        WriteStorage<'a, CombatStats>,
+                        WriteStorage<'a, SufferDamage>,
+                        ReadStorage<'a, Resistances>,
+                        ReadStorage<'a, Name>,
+                        WriteExpect<'a, GameLog> );
 
     fn run(&mut self, data : Self::SystemData) {
-        let (mut stats, mut damage) = data;
+        let (entities, mut stats, mut damage, resistances, names, mut log) = data;
 
-        for (mut stats, damage) in (&mut stats, &damage).join() {
-            stats.hp.current -= damage.amount.iter().sum::<i32>();
+        for (entity, mut stats, damage) in (&entities, &mut stats, &damage).join() {
+            for (amount, damage_type) in damage.amount.iter() {
+                let resistance = resistances.get(entity).map_or(0, |r| r.against(*damage_type));
+                // Rounded, and anything not fully resisted still gets at least a point through
+                let mut taken = i32::max(0, (amount * (100 - resistance) + 50) / 100);
+                if resistance < 100 && *amount > 0 {
+                    taken = i32::max(1, taken);
+                }
+                stats.hp.current -= taken;
+
+                if let Some(name) = names.get(entity) {
+                    if resistance >= 50 && *amount > 0 {
+                        log.entries.push(format!("{} shrugs off most of the {} damage.", &name.name, damage_type.name()));
+                    } else if resistance < 0 && taken > 0 {
+                        log.entries.push(format!("{} is badly hurt by the {} damage!", &name.name, damage_type.name()));
+                    }
+                }
+
+                // Burns heat up the suit as well
+                if *damage_type == DamageType::Thermal && stats.heat.max > 0 {
+                    stats.heat.current = i32::min(stats.heat.max, stats.heat.current + taken);
+                }
+            }
         }
 
         damage.clear();
@@ -20,11 +47,14 @@ impl<'a> System<'a> for DamageSystem {
 }
 pub fn delete_the_dead(ecs : &mut World) {
     let mut dead : Vec<Entity> = Vec::new();
+    let mut wrecks : Vec<(i32, i32, String, String)> = Vec::new();
     // Using a scope to make the borrow checker happy
     {
         let combat_stats = ecs.read_storage::<CombatStats>();
         let players = ecs.read_storage::<Player>();
         let names = ecs.read_storage::<Name>();
+        let loot_tables = ecs.read_storage::<LootTable>();
+        let positions = ecs.read_storage::<Position>();
         let entities = ecs.entities();
         let mut log = ecs.write_resource::<GameLog>();
         for (entity, stats) in (&entities, &combat_stats).join() {
@@ -36,6 +66,11 @@ pub fn delete_the_dead(ecs : &mut World) {
                         if let Some(victim_name) = victim_name {
                             log.entries.push(format!("{} is dead", &victim_name.name));
                         }
+                        // Destroyed robots leave something behind to salvage
+                        if let (Some(loot), Some(pos)) = (loot_tables.get(entity), positions.get(entity)) {
+                            let robot_name = names.get(entity).map_or("robot".to_string(), |name| name.name.clone());
+                            wrecks.push((pos.x, pos.y, robot_name, loot.table.clone()));
+                        }
                         dead.push(entity)
                     }
                     Some(_) => {
@@ -47,7 +82,30 @@ pub fn delete_the_dead(ecs : &mut World) {
         }
     }
 
+    // Anything the dead were carrying falls to the floor where they stood
+    {
+        let entities = ecs.entities();
+        let mut positions = ecs.write_storage::<Position>();
+        let mut backpack = ecs.write_storage::<InBackpack>();
+        let mut dropped : Vec<(Entity, i32, i32)> = Vec::new();
+        for (item, carried) in (&entities, &backpack).join() {
+            if dead.contains(&carried.owner) {
+                if let Some(pos) = positions.get(carried.owner) {
+                    dropped.push((item, pos.x, pos.y));
+                }
+            }
+        }
+        for (item, x, y) in dropped {
+            backpack.remove(item);
+            positions.insert(item, Position{ x, y }).expect("Unable to insert position");
+        }
+    }
+
     for victim in dead {
         ecs.delete_entity(victim).expect("Unable to delete");
     }
+
+    for (x, y, robot_name, table) in wrecks {
+        spawner::wreck(ecs, x, y, &robot_name, &table);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use super::{Map, MAP_COUNT};
+
+pub const DECK_COUNT : i32 = 3;
+
+// Keeps every deck the salvager already visited, so leaving one doesn't throw it away
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct DeckMaster {
+    maps : HashMap<i32, Map>
+}
+
+impl DeckMaster {
+    pub fn new() -> DeckMaster {
+        DeckMaster{ maps : HashMap::new() }
+    }
+
+    pub fn store_map(&mut self, map : &Map) {
+        self.maps.insert(map.depth, map.clone());
+    }
+
+    pub fn get_map(&self, depth : i32) -> Option<Map> {
+        if let Some(map) = self.maps.get(&depth) {
+            let mut result = map.clone();
+            result.tile_content = vec![Vec::new(); MAP_COUNT];
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
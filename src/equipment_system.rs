@@ -0,0 +1,65 @@
+use specs::prelude::*;
+use super::{CombatStats, Viewshed, Equipped, Pool, BaseStats, PoolBonus, DefenseBonus, ViewRangeBonus};
+
+pub struct EquipmentSystem {}
+
+impl<'a> System<'a> for EquipmentSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( Entities<'a>,
+                        ReadStorage<'a, BaseStats>,
+                        WriteStorage<'a, CombatStats>,
+                        WriteStorage<'a, Viewshed>,
+                        ReadStorage<'a, Equipped>,
+                        ReadStorage<'a, PoolBonus>,
+                        ReadStorage<'a, DefenseBonus>,
+                        ReadStorage<'a, ViewRangeBonus> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, base_stats, mut combat_stats, mut viewsheds, equipped, pool_bonuses, defense_bonuses, view_range_bonuses) = data;
+
+        // Rebuild the suit's stats from the baseline and whatever is currently equipped
+        for (entity, base, stats) in (&entities, &base_stats, &mut combat_stats).join() {
+            let mut hp = base.hp;
+            let mut energy = base.energy;
+            let mut oxygen = base.oxygen;
+            let mut defense = base.defense;
+            let mut view_range = base.view_range;
+
+            for (equipped_by, bonus) in (&equipped, &pool_bonuses).join() {
+                if equipped_by.owner == entity {
+                    hp += bonus.hp;
+                    energy += bonus.energy;
+                    oxygen += bonus.oxygen;
+                }
+            }
+            for (equipped_by, bonus) in (&equipped, &defense_bonuses).join() {
+                if equipped_by.owner == entity {
+                    defense += bonus.defense;
+                }
+            }
+            for (equipped_by, bonus) in (&equipped, &view_range_bonuses).join() {
+                if equipped_by.owner == entity {
+                    view_range += bonus.range;
+                }
+            }
+
+            set_max(&mut stats.hp, hp);
+            set_max(&mut stats.energy, energy);
+            set_max(&mut stats.oxygen, oxygen);
+            set_max(&mut stats.stamina, base.stamina);
+            stats.defense = defense;
+
+            if let Some(viewshed) = viewsheds.get_mut(entity) {
+                if viewshed.range != view_range {
+                    viewshed.range = view_range;
+                    viewshed.dirty = true;
+                }
+            }
+        }
+    }
+}
+
+fn set_max(pool : &mut Pool, max : i32) {
+    pool.max = max;
+    pool.current = i32::min(pool.current, max);
+}
@@ -0,0 +1,25 @@
+use crate::{Faction, FactionId};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum Reaction { Ignore, Attack }
+
+// Who goes for whom. The infected crew turn on the machines just as much as on the salvager.
+pub fn reaction(me : FactionId, other : FactionId) -> Reaction {
+    match (me, other) {
+        (FactionId::Salvagers, FactionId::Machines) => Reaction::Attack,
+        (FactionId::Salvagers, FactionId::Infected) => Reaction::Attack,
+        (FactionId::Machines, FactionId::Salvagers) => Reaction::Attack,
+        (FactionId::Machines, FactionId::Infected) => Reaction::Attack,
+        (FactionId::Infected, FactionId::Salvagers) => Reaction::Attack,
+        (FactionId::Infected, FactionId::Machines) => Reaction::Attack,
+        _ => Reaction::Ignore
+    }
+}
+
+// Anything without a faction is left alone by everyone
+pub fn is_hostile(me : Option<&Faction>, other : Option<&Faction>) -> bool {
+    match (me, other) {
+        (Some(me), Some(other)) => reaction(me.id, other.id) == Reaction::Attack,
+        _ => false
+    }
+}
@@ -0,0 +1,13 @@
+use rltk::RandomNumberGenerator;
+use serde::{Serialize, Deserialize};
+
+// Seed the current wreck was generated from and the turns played since, enough to replay a run
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameSeed {
+    pub seed : u64,
+    pub turn : i32
+}
+
+pub fn random_seed() -> u64 {
+    RandomNumberGenerator::new().range(1, 1_000_000_000) as u64
+}
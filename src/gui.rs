@@ -1,7 +1,10 @@
 use rltk::{Point, RGB, Rltk, VirtualKeyCode};
 use specs::prelude::*;
 
-use crate::{camera, Consumable, Equipped, RangedWeapon, Pool};
+use crate::{camera, Consumable, Equippable, Equipped, RangedWeapon, Pool, GameSeed, Target, BlocksTile, Magazine, FiringPattern};
+use crate::atmosphere_system::BREATHABLE_PRESSURE;
+use crate::power_system::{self, Subsystem};
+use crate::line_of_fire::{self, LineOfFire};
 
 use super::{
     CombatStats,
@@ -42,7 +45,7 @@ pub fn draw_ui(ecs: &World, ctx : &mut Rltk) {
     ctx.set(79, 8, box_gray, black, to_cp437('┤'));
     ctx.set(79, 45, box_gray, black, to_cp437('┤'));
 
-    let instruction_line = 24;
+    let instruction_line = 16;
     ctx.print(50, instruction_line, "Instructions");
     ctx.print(50, instruction_line+2, "Movement     Arrowkeys ");
     ctx.print(50, instruction_line+4, "Movement     Numpad(diagonal)");
@@ -54,13 +57,22 @@ pub fn draw_ui(ecs: &World, ctx : &mut Rltk) {
     ctx.print(50, instruction_line+16, "Ranged Weapon");
     ctx.print(50, instruction_line+18, "Target       V");
     ctx.print(50, instruction_line+20, "Shoot        F");
+    ctx.print(50, instruction_line+22, "Lift down/up . ,");
+    ctx.print(50, instruction_line+24, "Close door   C");
+    ctx.print(50, instruction_line+26, "Sprint       SHIFT-Direction");
+    ctx.print(50, instruction_line+28, "Rest         Space");
 
     let map = ecs.fetch::<Map>();
-    let name_length = map.name.len() + 2;
+    let player_pos = *ecs.fetch::<Point>();
+    let map_title = match map.room_type_at(player_pos.x, player_pos.y) {
+        Some(room_type) => format!("{} - Deck {} - {}", map.name, map.depth, room_type.name()),
+        None => format!("{} - Deck {}", map.name, map.depth)
+    };
+    let name_length = map_title.len() + 2;
     let x_pos = (22 - (name_length / 2)) as i32;
     ctx.set(x_pos, 0, box_gray, black, to_cp437('┤'));
     ctx.set(x_pos + name_length as i32, 0, box_gray, black, to_cp437('├'));
-    ctx.print_color(x_pos+1, 0, white, black, &map.name);
+    ctx.print_color(x_pos+1, 0, white, black, &map_title);
     std::mem::drop(map);
 
     // Draw stats
@@ -68,27 +80,59 @@ pub fn draw_ui(ecs: &World, ctx : &mut Rltk) {
     let stats = ecs.read_storage::<CombatStats>();
     let player_stats = stats.get(*player_entity).unwrap();
     draw_pool(ctx, black, white, &player_stats.hp, RGB::named(rltk::RED), 2);
-    draw_pool(ctx, black, white, &player_stats.energy, RGB::named(rltk::YELLOW), 4);
-    draw_pool(ctx, black, white, &player_stats.oxygen, RGB::named(rltk::BLUE), 6);
+    draw_pool(ctx, black, white, &player_stats.energy, RGB::named(rltk::YELLOW), 3);
+    draw_pool(ctx, black, white, &player_stats.oxygen, RGB::named(rltk::BLUE), 4);
+    draw_pool(ctx, black, white, &player_stats.stamina, RGB::named(rltk::GREEN), 5);
+    draw_pool(ctx, black, white, &player_stats.heat, RGB::named(rltk::ORANGE), 6);
+
+    let map = ecs.fetch::<Map>();
+    let player_pos = ecs.fetch::<Point>();
+    let pressure = map.atmosphere[map.xy_idx(player_pos.x, player_pos.y)];
+    let temperature = map.temperature[map.xy_idx(player_pos.x, player_pos.y)];
+    ctx.print_color(50, 1, white, black, &format!("Ambient: {}°C", temperature));
+    if map.alarm > 0 {
+        ctx.print_color(66, 1, RGB::named(rltk::RED), black, "ALARM");
+    }
+    if pressure >= BREATHABLE_PRESSURE {
+        ctx.print_color(50, 7, RGB::named(rltk::CYAN), black, &format!("Air pressure: {}%", pressure));
+    } else {
+        ctx.print_color(50, 7, RGB::named(rltk::RED), black, &format!("Low pressure: {}% - suit air", pressure));
+    }
+    std::mem::drop(map);
 
     // Equipped
     let mut y = 9;
     let entities = ecs.entities();
     let equipped = ecs.read_storage::<Equipped>();
     let ranged_weapon = ecs.read_storage::<RangedWeapon>();
+    let magazines = ecs.read_storage::<Magazine>();
+    let mut firing = (FiringPattern::Single, 0);
     for (entity, equipped_by) in (&entities, &equipped).join() {
         if equipped_by.owner == *player_entity {
             let name = get_item_display_name(ecs, entity);
-            ctx.print_color(50, y, white, black, &name);
+            ctx.print_color(50, y, white, black, &format!("{}: {}", equipped_by.slot.name(), name));
             y += 1;
 
             if let Some(weapon) = ranged_weapon.get(entity) {
-                let weapon_info = format!("┤ {} damage: {}, range: {}, F to fire, V cycle targets ├", &name, weapon.damage, weapon.range);
+                firing = (weapon.pattern, weapon.range);
+                let charge = magazines.get(entity).map_or(String::new(), |magazine| format!(", {}", magazine.describe()));
+                let weapon_info = format!("┤ {} dmg {}, rng {}, acc {:+}{} - F fire, V target, X reload ├", &name, weapon.damage, weapon.range, weapon.accuracy, charge);
                 ctx.print_color(3, 45, yellow, black, &weapon_info);
             }
         }
     }
 
+    // Preview the shot at the selected target
+    let targets = ecs.read_storage::<Target>();
+    let positions = ecs.read_storage::<Position>();
+    for (_target, target_pos) in (&targets, &positions).join() {
+        draw_shot_preview(ecs, ctx, *player_pos, Point::new(target_pos.x, target_pos.y), firing.0, firing.1);
+    }
+
+    let game_seed = ecs.fetch::<GameSeed>();
+    let seed_info = format!("┤ Seed: {}  Turn: {} ├", game_seed.seed, game_seed.turn);
+    ctx.print_color(3, 59, white, black, &seed_info);
+
 
     y += 1;
     let consumables = ecs.read_storage::<Consumable>();
@@ -239,13 +283,14 @@ pub fn show_inventory(gs : &mut State, ctx : &mut Rltk) -> (ItemMenuResult, Opti
     let player_entity = gs.ecs.fetch::<Entity>();
     let names = gs.ecs.read_storage::<Name>();
     let backpack = gs.ecs.read_storage::<InBackpack>();
+    let equippables = gs.ecs.read_storage::<Equippable>();
     let entities = gs.ecs.entities();
 
     let inventory = (&backpack, &names).join().filter(|item| item.0.owner == *player_entity );
     let count = inventory.count();
 
     let mut y = (25 - (count / 2)) as i32;
-    ctx.draw_box(15, y-2, 31, (count+3) as i32, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
+    ctx.draw_box(15, y-2, 40, (count+3) as i32, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
     ctx.print_color(18, y-2, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "Inventory");
     ctx.print_color(18, y+count as i32+1, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "ESCAPE to cancel");
 
@@ -256,7 +301,10 @@ pub fn show_inventory(gs : &mut State, ctx : &mut Rltk) -> (ItemMenuResult, Opti
         ctx.set(18, y, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), 97+j as rltk::FontCharType);
         ctx.set(19, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), rltk::to_cp437(')'));
 
-        ctx.print(21, y, &name.name.to_string());
+        match equippables.get(entity) {
+            Some(e) => ctx.print(21, y, &format!("{} [{}]", name.name, e.slot.name())),
+            None => ctx.print(21, y, &name.name.to_string()),
+        }
         equippable.push(entity);
         y += 1;
         j += 1;
@@ -323,7 +371,7 @@ pub fn drop_item_menu(gs : &mut State, ctx : &mut Rltk) -> (ItemMenuResult, Opti
     }
 }
 
-pub fn ranged_target(gs : &mut State, ctx : &mut Rltk, range : i32) -> (ItemMenuResult, Option<Point>) {
+pub fn ranged_target(gs : &mut State, ctx : &mut Rltk, range : i32, pattern : FiringPattern) -> (ItemMenuResult, Option<Point>) {
     let (min_x, max_x, min_y, max_y) = camera::get_screen_bounds(&gs.ecs);
     let player_entity = gs.ecs.fetch::<Entity>();
     let player_pos = gs.ecs.fetch::<Point>();
@@ -359,6 +407,7 @@ pub fn ranged_target(gs : &mut State, ctx : &mut Rltk, range : i32) -> (ItemMenu
     let mut valid_target = false;
     for idx in available_cells.iter() { if idx.x == mouse_map_pos.0 && idx.y == mouse_map_pos.1 { valid_target = true; } }
     if valid_target {
+        draw_shot_preview(&gs.ecs, ctx, *player_pos, Point::new(mouse_map_pos.0, mouse_map_pos.1), pattern, range);
         ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::CYAN));
         if ctx.left_click {
             return (ItemMenuResult::Selected, Some(Point::new(mouse_map_pos.0, mouse_map_pos.1)));
@@ -378,8 +427,105 @@ pub fn ranged_target(gs : &mut State, ctx : &mut Rltk, range : i32) -> (ItemMenu
 }
 
 
+// Shades the projected shot, the part behind the first obstacle in red
+pub fn draw_fire_line(ecs : &World, ctx : &mut Rltk, from : Point, to : Point) {
+    let (min_x, max_x, min_y, max_y) = camera::get_screen_bounds(ecs);
+    let map = ecs.fetch::<Map>();
+    let blockers = ecs.read_storage::<BlocksTile>();
+
+    let blocked_at = match line_of_fire::trace(&map, &blockers, from, to) {
+        LineOfFire::Blocked{ at } => Some(at),
+        LineOfFire::Absorbed{ by } => ecs.read_storage::<Position>().get(by).map(|pos| Point::new(pos.x, pos.y)),
+        LineOfFire::Clear{ .. } => None
+    };
+
+    let mut blocked = false;
+    for step in line_of_fire::fire_path(from, to).iter() {
+        if Some(*step) == blocked_at { blocked = true; }
+        let screen_x = step.x - min_x;
+        let screen_y = step.y - min_y;
+        if screen_x > 0 && screen_x < (max_x - min_x) && screen_y > 0 && screen_y < (max_y - min_y) {
+            if blocked {
+                ctx.set_bg(screen_x, screen_y, RGB::named(rltk::DARK_RED));
+            } else {
+                ctx.set_bg(screen_x, screen_y, RGB::named(rltk::DARK_GREEN));
+            }
+        }
+    }
+}
+
+// Shows which tiles a shot with the given firing pattern would reach
+pub fn draw_shot_preview(ecs : &World, ctx : &mut Rltk, from : Point, to : Point, pattern : FiringPattern, range : i32) {
+    match pattern {
+        FiringPattern::Single | FiringPattern::Scoped => draw_fire_line(ecs, ctx, from, to),
+        FiringPattern::Spread{ radius } => {
+            draw_fire_line(ecs, ctx, from, to);
+            let area = line_of_fire::spread_area(&ecs.fetch::<Map>(), from, to, radius);
+            highlight_tiles(ecs, ctx, &area, RGB::named(rltk::DARK_ORANGE));
+        }
+        FiringPattern::Beam => {
+            let path = line_of_fire::beam_path(&ecs.fetch::<Map>(), from, to, range);
+            highlight_tiles(ecs, ctx, &path, RGB::named(rltk::DARK_GREEN));
+        }
+    }
+}
+
+fn highlight_tiles(ecs : &World, ctx : &mut Rltk, tiles : &[Point], color : RGB) {
+    let (min_x, max_x, min_y, max_y) = camera::get_screen_bounds(ecs);
+    for tile in tiles.iter() {
+        let screen_x = tile.x - min_x;
+        let screen_y = tile.y - min_y;
+        if screen_x > 0 && screen_x < (max_x - min_x) && screen_y > 0 && screen_y < (max_y - min_y) {
+            ctx.set_bg(screen_x, screen_y, color);
+        }
+    }
+}
+
+pub fn power_console(gs : &mut State, ctx : &mut Rltk) -> (ItemMenuResult, Option<Subsystem>) {
+    let map = gs.ecs.fetch::<Map>();
+    let subsystems = [Subsystem::LifeSupport, Subsystem::Heating, Subsystem::Lighting, Subsystem::Doors, Subsystem::Sensors, Subsystem::Fabrication];
+    let count = subsystems.len();
+
+    let mut y = (25 - (count / 2)) as i32;
+    ctx.draw_box(15, y-2, 31, (count+5) as i32, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
+    ctx.print_color(18, y-2, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "Power Console");
+    ctx.print_color(17, y-1, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), &format!("Grid load: {}/{}", map.power.demand(), map.power.capacity));
+    ctx.print_color(18, y+count as i32+3, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "ESCAPE to cancel");
+
+    y += 1;
+    for (j, subsystem) in subsystems.iter().enumerate() {
+        ctx.set(17, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), rltk::to_cp437('('));
+        ctx.set(18, y, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), 97+j as rltk::FontCharType);
+        ctx.set(19, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), rltk::to_cp437(')'));
+
+        ctx.print(21, y, power_system::subsystem_name(*subsystem));
+        if map.power.is_on(*subsystem) {
+            ctx.print_color(40, y, RGB::named(rltk::GREEN), RGB::named(rltk::BLACK), "ON");
+        } else {
+            ctx.print_color(40, y, RGB::named(rltk::RED), RGB::named(rltk::BLACK), "OFF");
+        }
+        y += 1;
+    }
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None),
+        Some(key) => {
+            match key {
+                VirtualKeyCode::Escape => { (ItemMenuResult::Cancel, None) }
+                _ => {
+                    let selection = rltk::letter_to_option(key);
+                    if selection > -1 && selection < count as i32 {
+                        return (ItemMenuResult::Selected, Some(subsystems[selection as usize]));
+                    }
+                    (ItemMenuResult::NoResponse, None)
+                }
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Copy, Clone)]
-pub enum MainMenuSelection { NewGame, LoadGame, Quit }
+pub enum MainMenuSelection { NewGame, EnterSeed, LoadGame, Quit }
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum MainMenuResult { NoSelection{ selected : MainMenuSelection }, Selected{ selected: MainMenuSelection } }
@@ -397,18 +543,24 @@ pub fn main_menu(gs : &mut State, ctx : &mut Rltk) -> MainMenuResult {
             ctx.print_color_centered(24, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Begin New Game");
         }
 
+        if selection == MainMenuSelection::EnterSeed {
+            ctx.print_color_centered(25, RGB::named(rltk::MAGENTA), RGB::named(rltk::BLACK), "Begin Seeded Game");
+        } else {
+            ctx.print_color_centered(25, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Begin Seeded Game");
+        }
+
         if save_exists {
             if selection == MainMenuSelection::LoadGame {
-                ctx.print_color_centered(25, RGB::named(rltk::MAGENTA), RGB::named(rltk::BLACK), "Load Game");
+                ctx.print_color_centered(26, RGB::named(rltk::MAGENTA), RGB::named(rltk::BLACK), "Load Game");
             } else {
-                ctx.print_color_centered(25, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Load Game");
+                ctx.print_color_centered(26, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Load Game");
             }
         }
 
         if selection == MainMenuSelection::Quit {
-            ctx.print_color_centered(26, RGB::named(rltk::MAGENTA), RGB::named(rltk::BLACK), "Quit");
+            ctx.print_color_centered(27, RGB::named(rltk::MAGENTA), RGB::named(rltk::BLACK), "Quit");
         } else {
-            ctx.print_color_centered(26, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Quit");
+            ctx.print_color_centered(27, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Quit");
         }
 
         match ctx.key {
@@ -420,7 +572,8 @@ pub fn main_menu(gs : &mut State, ctx : &mut Rltk) -> MainMenuResult {
                         let newselection;
                         match selection {
                             MainMenuSelection::NewGame => newselection = MainMenuSelection::Quit,
-                            MainMenuSelection::LoadGame => newselection = MainMenuSelection::NewGame,
+                            MainMenuSelection::EnterSeed => newselection = MainMenuSelection::NewGame,
+                            MainMenuSelection::LoadGame => newselection = MainMenuSelection::EnterSeed,
                             MainMenuSelection::Quit => newselection = MainMenuSelection::LoadGame
                         }
                         return MainMenuResult::NoSelection{ selected: newselection }
@@ -428,7 +581,8 @@ pub fn main_menu(gs : &mut State, ctx : &mut Rltk) -> MainMenuResult {
                     VirtualKeyCode::Down => {
                         let newselection;
                         match selection {
-                            MainMenuSelection::NewGame => newselection = MainMenuSelection::LoadGame,
+                            MainMenuSelection::NewGame => newselection = MainMenuSelection::EnterSeed,
+                            MainMenuSelection::EnterSeed => newselection = MainMenuSelection::LoadGame,
                             MainMenuSelection::LoadGame => newselection = MainMenuSelection::Quit,
                             MainMenuSelection::Quit => newselection = MainMenuSelection::NewGame
                         }
@@ -444,6 +598,56 @@ pub fn main_menu(gs : &mut State, ctx : &mut Rltk) -> MainMenuResult {
     MainMenuResult::NoSelection { selected: MainMenuSelection::NewGame }
 }
 
+#[derive(PartialEq, Copy, Clone)]
+pub enum SeedEntryResult { Cancel, NoResponse{ seed : u64 }, Selected{ seed : u64 } }
+
+pub fn seed_entry(gs : &mut State, ctx : &mut Rltk) -> SeedEntryResult {
+    let runstate = gs.ecs.fetch::<RunState>();
+
+    ctx.print_color_centered(15, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "The Salvager");
+
+    if let RunState::EnterSeed{ seed } = *runstate {
+        ctx.print_color_centered(24, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Enter Seed:");
+        ctx.print_color_centered(25, RGB::named(rltk::MAGENTA), RGB::named(rltk::BLACK), &format!("{}_", seed));
+        ctx.print_color_centered(27, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "ENTER to start, ESCAPE to cancel");
+
+        match ctx.key {
+            None => return SeedEntryResult::NoResponse{ seed },
+            Some(key) => {
+                let digit : Option<u64> =
+                    match key {
+                        VirtualKeyCode::Key0 | VirtualKeyCode::Numpad0 => Some(0),
+                        VirtualKeyCode::Key1 | VirtualKeyCode::Numpad1 => Some(1),
+                        VirtualKeyCode::Key2 | VirtualKeyCode::Numpad2 => Some(2),
+                        VirtualKeyCode::Key3 | VirtualKeyCode::Numpad3 => Some(3),
+                        VirtualKeyCode::Key4 | VirtualKeyCode::Numpad4 => Some(4),
+                        VirtualKeyCode::Key5 | VirtualKeyCode::Numpad5 => Some(5),
+                        VirtualKeyCode::Key6 | VirtualKeyCode::Numpad6 => Some(6),
+                        VirtualKeyCode::Key7 | VirtualKeyCode::Numpad7 => Some(7),
+                        VirtualKeyCode::Key8 | VirtualKeyCode::Numpad8 => Some(8),
+                        VirtualKeyCode::Key9 | VirtualKeyCode::Numpad9 => Some(9),
+                        _ => None
+                    };
+                if let Some(digit) = digit {
+                    // Cap the length so the seed stays readable in the UI
+                    if seed < 100_000_000 {
+                        return SeedEntryResult::NoResponse{ seed: seed * 10 + digit };
+                    }
+                    return SeedEntryResult::NoResponse{ seed };
+                }
+                match key {
+                    VirtualKeyCode::Escape => return SeedEntryResult::Cancel,
+                    VirtualKeyCode::Back => return SeedEntryResult::NoResponse{ seed: seed / 10 },
+                    VirtualKeyCode::Return => return SeedEntryResult::Selected{ seed },
+                    _ => return SeedEntryResult::NoResponse{ seed }
+                }
+            }
+        }
+    }
+
+    SeedEntryResult::Cancel
+}
+
 #[derive(PartialEq, Copy, Clone)]
 pub enum GameEndResult { NoSelection, QuitToMenu }
 
@@ -503,18 +707,18 @@ pub fn remove_item_menu(gs : &mut State, ctx : &mut Rltk) -> (ItemMenuResult, Op
     let count = inventory.count();
 
     let mut y = (25 - (count / 2)) as i32;
-    ctx.draw_box(15, y-2, 31, (count+3) as i32, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
+    ctx.draw_box(15, y-2, 40, (count+3) as i32, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
     ctx.print_color(18, y-2, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "Remove Which Item?");
     ctx.print_color(18, y+count as i32+1, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "ESCAPE to cancel");
 
     let mut equippable : Vec<Entity> = Vec::new();
     let mut j = 0;
-    for (entity, _pack, name) in (&entities, &backpack, &names).join().filter(|item| item.1.owner == *player_entity ) {
+    for (entity, equipped, name) in (&entities, &backpack, &names).join().filter(|item| item.1.owner == *player_entity ) {
         ctx.set(17, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), rltk::to_cp437('('));
         ctx.set(18, y, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), 97+j as rltk::FontCharType);
         ctx.set(19, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), rltk::to_cp437(')'));
 
-        ctx.print(21, y, &name.name.to_string());
+        ctx.print(21, y, &format!("{} [{}]", name.name, equipped.slot.name()));
         equippable.push(entity);
         y += 1;
         j += 1;
@@ -0,0 +1,95 @@
+use specs::prelude::*;
+use super::{gamelog::GameLog, CombatStats, Name, Renderable, BlocksTile};
+use crate::{AiState, Brain, Companion, DrainEnergy, Faction, FactionId, WantsToHack};
+use crate::faction;
+use rltk::{RandomNumberGenerator, RGB};
+
+// Energy the intrusion draws from the salvager's suit
+pub const HACK_ENERGY_COST : i32 = 5;
+const HACK_BASE_CHANCE : i32 = 60;
+const MIN_HACK_CHANCE : i32 = 5;
+const MAX_HACK_CHANCE : i32 = 95;
+
+pub struct HackingSystem {}
+
+impl<'a> System<'a> for HackingSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( Entities<'a>,
+                        ReadExpect<'a, Entity>,
+                        WriteExpect<'a, GameLog>,
+                        WriteExpect<'a, RandomNumberGenerator>,
+                        WriteStorage<'a, WantsToHack>,
+                        ReadStorage<'a, CombatStats>,
+                        WriteStorage<'a, DrainEnergy>,
+                        WriteStorage<'a, Name>,
+                        WriteStorage<'a, Renderable>,
+                        WriteStorage<'a, Brain>,
+                        WriteStorage<'a, Faction>,
+                        WriteStorage<'a, BlocksTile>,
+                        WriteStorage<'a, Companion> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, player_entity, mut log, mut rng, mut wants_hack, combat_stats, mut drain_energy,
+            mut names, mut renderables, mut brains, mut factions, mut blockers, mut companions) = data;
+
+        for (entity, hack) in (&entities, &wants_hack).join() {
+            let (hacker_stats, target_stats) = match (combat_stats.get(entity), combat_stats.get(hack.target)) {
+                (Some(hacker), Some(target)) => (hacker, target),
+                _ => continue
+            };
+            let target_name = names.get(hack.target).map_or("robot".to_string(), |name| name.name.clone());
+
+            // Companions and shut-down robots have nothing left to take over
+            if !faction::is_hostile(factions.get(entity), factions.get(hack.target)) {
+                if entity == *player_entity {
+                    log.entries.push(format!("The {} isn't hostile, there is nothing to hack.", target_name));
+                }
+                continue;
+            }
+
+            if hacker_stats.energy.current < HACK_ENERGY_COST {
+                if entity == *player_entity {
+                    log.entries.push("Your suit doesn't have the energy to run the intrusion.".to_string());
+                }
+                continue;
+            }
+            DrainEnergy::new_energy(&mut drain_energy, entity, HACK_ENERGY_COST);
+
+            // Damaged robots have fewer working countermeasures left
+            let chance = i32::max(MIN_HACK_CHANCE, i32::min(MAX_HACK_CHANCE, HACK_BASE_CHANCE + hack.bonus - target_stats.hp.current * 2));
+            let roll = rng.roll_dice(1, 100);
+
+            if roll <= chance / 2 {
+                // A clean intrusion rewrites its friend-or-foe tables
+                if let Some(faction) = factions.get_mut(hack.target) {
+                    faction.id = FactionId::Salvagers;
+                }
+                if let Some(brain) = brains.get_mut(hack.target) {
+                    brain.state = AiState::Patrol;
+                    brain.last_seen = None;
+                }
+                companions.insert(hack.target, Companion{}).expect("Unable to insert companion");
+                if let Some(render) = renderables.get_mut(hack.target) {
+                    render.fg = RGB::named(rltk::CYAN);
+                }
+                log.entries.push(format!("You reprogram the {}, it now follows you.", target_name));
+            } else if roll <= chance {
+                // Only enough access to pull the plug
+                brains.remove(hack.target);
+                factions.remove(hack.target);
+                blockers.remove(hack.target);
+                if let Some(render) = renderables.get_mut(hack.target) {
+                    render.fg = RGB::named(rltk::GRAY);
+                }
+                if let Some(name) = names.get_mut(hack.target) {
+                    name.name = format!("Deactivated {}", target_name);
+                }
+                log.entries.push(format!("You shut the {} down.", target_name));
+            } else {
+                log.entries.push(format!("The {}'s firewall locks you out.", target_name));
+            }
+        }
+
+        wants_hack.clear();
+    }
+}
@@ -0,0 +1,93 @@
+use specs::prelude::*;
+use super::{Map, TileType, Rect};
+use crate::initiative_system::GameClock;
+use crate::atmosphere_system::holds_air;
+use crate::room_types::RoomType;
+use rltk::RandomNumberGenerator;
+
+pub const COMFORT_TEMPERATURE : i32 = 20;
+const COLD_TEMPERATURE : i32 = -60;
+const ENGINE_TEMPERATURE : i32 = 90;
+pub const FIRE_TEMPERATURE : i32 = 150;
+const DRIFT_RATE : i32 = 1;
+const FIRE_CHANCE : i32 = 15;
+
+pub struct HeatSystem {}
+
+impl<'a> System<'a> for HeatSystem {
+    type SystemData = ( WriteExpect<'a, Map>,
+                        ReadExpect<'a, GameClock> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (mut map, clock) = data;
+
+        if !clock.ticked { return }
+
+        // Without heating the deck slowly loses its warmth to space
+        let ambient = if map.power.heating { COMFORT_TEMPERATURE } else { COLD_TEMPERATURE };
+
+        let mut new_temperature = map.temperature.clone();
+        for idx in 0..map.tiles.len() {
+            if !holds_air(&map, idx) { continue; }
+            if map.heat_sources[idx] != 0 {
+                new_temperature[idx] = map.heat_sources[idx];
+                continue;
+            }
+
+            // Heat spreads to the neighbouring tiles the same way air does
+            let x = idx as i32 % map.width;
+            let y = idx as i32 / map.width;
+            let mut total = map.temperature[idx];
+            let mut count = 1;
+            for (nx, ny) in [(x-1, y), (x+1, y), (x, y-1), (x, y+1)].iter() {
+                if *nx < 0 || *nx >= map.width || *ny < 0 || *ny >= map.height { continue; }
+                let neighbour = map.xy_idx(*nx, *ny);
+                if holds_air(&map, neighbour) {
+                    total += map.temperature[neighbour];
+                    count += 1;
+                }
+            }
+            let mut temperature = total / count;
+            if temperature > ambient {
+                temperature = i32::max(ambient, temperature - DRIFT_RATE);
+            } else if temperature < ambient {
+                temperature = i32::min(ambient, temperature + DRIFT_RATE);
+            }
+            new_temperature[idx] = temperature;
+        }
+        map.temperature = new_temperature;
+    }
+}
+
+// Puts the engines in one of the deck's rooms and lets a few others catch fire
+pub fn place_heat_sources(map : &mut Map, rng : &mut RandomNumberGenerator) {
+    for idx in 0..map.temperature.len() {
+        map.temperature[idx] = COMFORT_TEMPERATURE;
+    }
+
+    let rooms : Vec<Rect> = map.rooms.iter().skip(1).cloned().collect();
+    if rooms.is_empty() { return; }
+
+    // The engine sits in the engine room, the docking room is never one
+    let engine_room = match map.room_types.iter().position(|room_type| *room_type == RoomType::EngineRoom) {
+        Some(i) if i > 0 => i - 1,
+        _ => rng.random_slice_index(&rooms).unwrap()
+    };
+    let (engine_x, engine_y) = rooms[engine_room].center();
+    let engine_idx = map.xy_idx(engine_x, engine_y);
+    if map.tiles[engine_idx] == TileType::Floor {
+        map.heat_sources[engine_idx] = ENGINE_TEMPERATURE;
+        map.temperature[engine_idx] = ENGINE_TEMPERATURE;
+    }
+
+    for (i, room) in rooms.iter().enumerate() {
+        if i == engine_room || rng.roll_dice(1, 100) > FIRE_CHANCE { continue; }
+        let x = room.x1 + rng.roll_dice(1, i32::abs(room.x2 - room.x1));
+        let y = room.y1 + rng.roll_dice(1, i32::abs(room.y2 - room.y1));
+        let idx = map.xy_idx(x, y);
+        if map.tiles[idx] == TileType::Floor && map.atmosphere[idx] > 0 {
+            map.heat_sources[idx] = FIRE_TEMPERATURE;
+            map.temperature[idx] = FIRE_TEMPERATURE;
+        }
+    }
+}
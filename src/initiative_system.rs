@@ -0,0 +1,91 @@
+use specs::prelude::*;
+use super::{Position, RunState};
+use crate::{Brain, BreathOxygen, Initiative, TimeCost};
+
+// Game time a standard action takes, speeds are measured against it
+pub const TURN_COST : i32 = 100;
+pub const SPRINT_TIME : i32 = 150;
+pub const AIM_TIME : i32 = 50;
+pub const RELOAD_TIME : i32 = 150;
+
+// Whether game time moved on during this run of the systems, the environment only changes when it does
+#[derive(Default)]
+pub struct GameClock {
+    pub ticked : bool
+}
+
+pub struct InitiativeSystem {}
+
+impl<'a> System<'a> for InitiativeSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( Entities<'a>,
+                        WriteStorage<'a, Initiative>,
+                        WriteStorage<'a, TimeCost>,
+                        ReadStorage<'a, Brain>,
+                        ReadStorage<'a, Position>,
+                        WriteStorage<'a, BreathOxygen>,
+                        ReadExpect<'a, Entity>,
+                        ReadExpect<'a, RunState>,
+                        WriteExpect<'a, GameClock> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, mut initiatives, mut time_costs, brains, positions, mut breaths, player_entity, runstate, mut clock) = data;
+
+        clock.ticked = false;
+        match *runstate {
+            RunState::PlayerTurn => {
+                // The player pays for whatever they just did, most things take a standard turn
+                let cost = time_costs.get(*player_entity).map_or(TURN_COST, |time| time.amount);
+                if let Some(initiative) = initiatives.get_mut(*player_entity) {
+                    initiative.energy -= cost;
+                }
+            }
+            RunState::MonsterTurn => {
+                // Time only moves on once nobody, the player included, has an action banked any more
+                if (&initiatives, &brains, &positions).join().any(|(initiative, _, _)| initiative.energy >= 0) { return; }
+                if initiatives.get(*player_entity).map_or(false, |initiative| initiative.energy >= 0) { return; }
+
+                clock.ticked = true;
+                for (entity, initiative, _pos) in (&entities, &mut initiatives, &positions).join() {
+                    initiative.energy += initiative.speed;
+                    // Breathing goes by the clock, not by how many keys were pressed
+                    if entity == *player_entity {
+                        BreathOxygen::new_breath(&mut breaths, entity, 1);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        time_costs.clear();
+    }
+}
+
+// A quick action can leave the player with enough time banked to go again before anyone else moves
+pub fn after_player_turn(ecs : &World) -> RunState {
+    let initiatives = ecs.read_storage::<Initiative>();
+    let player_entity = ecs.fetch::<Entity>();
+
+    if initiatives.get(*player_entity).map_or(false, |initiative| initiative.energy >= 0) {
+        RunState::AwaitingInput
+    } else {
+        RunState::MonsterTurn
+    }
+}
+
+// Fast actors with another action banked get a further pass before time moves on, and time keeps moving until the player is ready
+pub fn after_monster_turn(ecs : &World) -> RunState {
+    let initiatives = ecs.read_storage::<Initiative>();
+    let brains = ecs.read_storage::<Brain>();
+    let positions = ecs.read_storage::<Position>();
+    let player_entity = ecs.fetch::<Entity>();
+
+    if (&initiatives, &brains, &positions).join().any(|(initiative, _, _)| initiative.energy >= 0) {
+        return RunState::MonsterTurn;
+    }
+    if initiatives.get(*player_entity).map_or(true, |initiative| initiative.energy >= 0) {
+        RunState::AwaitingInput
+    } else {
+        RunState::MonsterTurn
+    }
+}
@@ -16,7 +16,8 @@ use super::{
     AreaOfEffect,
     Confusion
 };
-use crate::{Equippable, Equipped, WantsToRemoveItem, ProvidesOxygen, ProvidesEnergy};
+use crate::{Equippable, Equipped, WantsToRemoveItem, ProvidesOxygen, ProvidesEnergy, HackingTool, WantsToHack, Robot, Faction};
+use crate::faction;
 
 pub struct ItemCollectionSystem {}
 
@@ -67,7 +68,11 @@ impl<'a> System<'a> for ItemUseSystem {
                         WriteStorage<'a, Equipped>,
                         WriteStorage<'a, InBackpack>,
                         WriteStorage<'a,ProvidesOxygen>,
-                        WriteStorage<'a,ProvidesEnergy>
+                        WriteStorage<'a,ProvidesEnergy>,
+                        ReadStorage<'a, HackingTool>,
+                        ReadStorage<'a, Robot>,
+                        WriteStorage<'a, WantsToHack>,
+                        ReadStorage<'a, Faction>
                       );
 
     fn run(&mut self, data : Self::SystemData) {
@@ -89,7 +94,11 @@ impl<'a> System<'a> for ItemUseSystem {
             mut equipped,
             mut backpack,
             oxygens,
-            energies
+            energies,
+            hacking_tools,
+            robots,
+            mut wants_hack,
+            factions
         ) = data;
 
         for (entity, useitem) in (&entities, &wants_use).join() {
@@ -213,11 +222,11 @@ impl<'a> System<'a> for ItemUseSystem {
                 Some(damage) => {
                     used_item = false;
                     for mob in targets.iter() {
-                        SufferDamage::new_damage(&mut suffer_damage, *mob, damage.damage);
+                        SufferDamage::new_damage(&mut suffer_damage, *mob, damage.damage, damage.damage_type);
                         if entity == *player_entity {
                             let mob_name = names.get(*mob).unwrap();
                             let item_name = names.get(useitem.item).unwrap();
-                            gamelog.entries.push(format!("You use {} on {}, inflicting {} hp.", item_name.name, mob_name.name, damage.damage));
+                            gamelog.entries.push(format!("You use {} on {}, inflicting {} {} damage.", item_name.name, mob_name.name, damage.damage, damage.damage_type.name()));
                         }
                         used_item = true;
                     }
@@ -247,6 +256,25 @@ impl<'a> System<'a> for ItemUseSystem {
                 confused.insert(mob.0, Confusion{ turns: mob.1 }).expect("Unable to insert status");
             }
 
+            // Hacking tools start an intrusion, the hacking system works out whether it gets in
+            let hacking_tool = hacking_tools.get(useitem.item);
+            match hacking_tool {
+                None => {}
+                Some(tool) => {
+                    used_item = false;
+                    // Only robots still working for the other side are worth breaking into
+                    for mob in targets.iter() {
+                        if robots.get(*mob).is_some() && faction::is_hostile(factions.get(entity), factions.get(*mob)) {
+                            wants_hack.insert(entity, WantsToHack{ target: *mob, bonus: tool.bonus }).expect("Unable to insert hack");
+                            used_item = true;
+                        }
+                    }
+                    if !used_item && entity == *player_entity {
+                        gamelog.entries.push("There is no hostile robot there to hack.".to_string());
+                    }
+                }
+            }
+
             if used_item {
                 let consumable = consumables.get(useitem.item);
                 match consumable {
@@ -0,0 +1,109 @@
+use specs::prelude::*;
+use super::{Map, BlocksTile};
+use rltk::Point;
+
+const COVER_PER_WALL : i32 = 25;
+const MAX_COVER : i32 = 50;
+
+pub enum LineOfFire {
+    // Nothing in the way, but walls next to the target may still give it cover (in percent)
+    Clear { cover : i32 },
+    // A wall, closed door or console stops the shot here
+    Blocked { at : Point },
+    // Someone standing in the line takes the shot instead
+    Absorbed { by : Entity }
+}
+
+// The tiles a shot passes through, from the first step after the shooter up to the target
+pub fn fire_path(from : Point, to : Point) -> Vec<Point> {
+    rltk::line2d(rltk::LineAlg::Bresenham, from, to).into_iter().filter(|p| *p != from).collect()
+}
+
+pub fn stops_shots(map : &Map, idx : usize) -> bool {
+    map.tiles[idx].is_solid()
+}
+
+fn in_bounds(map : &Map, point : Point) -> bool {
+    point.x >= 0 && point.x < map.width && point.y >= 0 && point.y < map.height
+}
+
+// True if no wall stands between the two points, entities are ignored
+pub fn path_is_open(map : &Map, from : Point, to : Point) -> bool {
+    fire_path(from, to).iter().all(|step| in_bounds(map, *step) && !stops_shots(map, map.xy_idx(step.x, step.y)))
+}
+
+// Every tile a beam crosses, carried on past the target until the weapon's range runs out or a wall stops it
+pub fn beam_path(map : &Map, from : Point, to : Point, range : i32) -> Vec<Point> {
+    let distance = rltk::DistanceAlg::Pythagoras.distance2d(from, to);
+    if distance < 1.0 { return Vec::new(); }
+    let scale = range as f32 / distance;
+    let end = Point::new(
+        from.x + ((to.x - from.x) as f32 * scale).round() as i32,
+        from.y + ((to.y - from.y) as f32 * scale).round() as i32
+    );
+
+    let mut path = Vec::new();
+    for step in fire_path(from, end).iter() {
+        if !in_bounds(map, *step) || stops_shots(map, map.xy_idx(step.x, step.y)) { break; }
+        path.push(*step);
+    }
+    path
+}
+
+// The cone pellets fan out in from the shooter, widening until it is radius tiles either side of the aimed-at spot.
+// Anything behind a wall is safe.
+pub fn spread_area(map : &Map, from : Point, to : Point, radius : i32) -> Vec<Point> {
+    let mut area = Vec::new();
+    let distance = rltk::DistanceAlg::Pythagoras.distance2d(from, to);
+    if distance < 1.0 { return area; }
+    let (dir_x, dir_y) = ((to.x - from.x) as f32 / distance, (to.y - from.y) as f32 / distance);
+
+    for y in i32::min(from.y, to.y) - radius ..= i32::max(from.y, to.y) + radius {
+        for x in i32::min(from.x, to.x) - radius ..= i32::max(from.x, to.x) + radius {
+            let tile = Point::new(x, y);
+            if !in_bounds(map, tile) || tile == from { continue; }
+
+            // How far along the line of fire the tile lies, and how far off to the side
+            let (offset_x, offset_y) = ((x - from.x) as f32, (y - from.y) as f32);
+            let along = offset_x * dir_x + offset_y * dir_y;
+            let aside = (offset_x * dir_y - offset_y * dir_x).abs();
+            if along <= 0.0 || along > distance + 0.5 { continue; }
+            if aside > radius as f32 * along / distance + 0.5 { continue; }
+
+            if path_is_open(map, from, tile) {
+                area.push(tile);
+            }
+        }
+    }
+    area
+}
+
+pub fn trace(map : &Map, blockers : &ReadStorage<BlocksTile>, from : Point, to : Point) -> LineOfFire {
+    for step in fire_path(from, to).iter() {
+        let idx = map.xy_idx(step.x, step.y);
+        if stops_shots(map, idx) {
+            return LineOfFire::Blocked{ at : *step };
+        }
+        if *step == to { break; }
+        for entity in map.tile_content[idx].iter() {
+            if blockers.get(*entity).is_some() {
+                return LineOfFire::Absorbed{ by : *entity };
+            }
+        }
+    }
+
+    // Walls around the target on the shooter's side count as cover
+    let range = rltk::DistanceAlg::Pythagoras.distance2d(from, to);
+    let mut cover = 0;
+    for delta_y in -1 ..= 1 {
+        for delta_x in -1 ..= 1 {
+            let neighbour = Point::new(to.x + delta_x, to.y + delta_y);
+            if neighbour == to || neighbour.x < 0 || neighbour.x >= map.width || neighbour.y < 0 || neighbour.y >= map.height { continue; }
+            if stops_shots(map, map.xy_idx(neighbour.x, neighbour.y)) && rltk::DistanceAlg::Pythagoras.distance2d(from, neighbour) < range {
+                cover += COVER_PER_WALL;
+            }
+        }
+    }
+
+    LineOfFire::Clear{ cover : i32::min(cover, MAX_COVER) }
+}
@@ -1,5 +1,5 @@
 use rltk::Point;
-use rltk::{Rltk, GameState};
+use rltk::{Rltk, GameState, RandomNumberGenerator};
 use specs::prelude::*;
 use specs::saveload::{SimpleMarker, SimpleMarkerAllocator};
 extern crate serde;
@@ -8,6 +8,7 @@ mod components;
 pub use components::*;
 mod map;
 pub use map::*;
+mod map_builders;
 mod player;
 use player::*;
 mod rect;
@@ -25,13 +26,22 @@ use damage_system::DamageSystem;
 mod gui;
 use gui::MainMenuSelection;
 mod gamelog;
+mod game_seed;
+use game_seed::GameSeed;
+mod deck_master;
+use deck_master::{DeckMaster, DECK_COUNT};
 mod spawner;
+mod random_table;
+mod raws;
 mod inventory_system;
 use inventory_system::ItemCollectionSystem;
 use inventory_system::ItemUseSystem;
 use inventory_system::ItemDropSystem;
 use crate::inventory_system::ItemRemoveSystem;
 mod ranged_combat_system;
+mod line_of_fire;
+mod attack_roll;
+mod faction;
 use ranged_combat_system::RangedCombatSystem;
 
 mod saveload_system;
@@ -40,6 +50,27 @@ mod energy_system;
 use energy_system::EnergySystem;
 mod oxygen_system;
 use oxygen_system::OxygenSystem;
+mod atmosphere_system;
+use atmosphere_system::AtmosphereSystem;
+mod power_system;
+use power_system::PowerSystem;
+mod equipment_system;
+use equipment_system::EquipmentSystem;
+mod stamina_system;
+use stamina_system::StaminaSystem;
+mod heat_system;
+use heat_system::HeatSystem;
+mod suit_heat_system;
+use suit_heat_system::SuitHeatSystem;
+mod noise_system;
+use noise_system::NoiseSystem;
+mod initiative_system;
+use initiative_system::{InitiativeSystem, GameClock};
+mod hacking_system;
+use hacking_system::HackingSystem;
+mod alarm_system;
+mod room_types;
+use alarm_system::AlarmSystem;
 use crate::gamelog::GameLog;
 
 
@@ -53,10 +84,14 @@ pub enum RunState {
     ShowDropItem,
     ShowTargeting { range : i32, item : Entity} ,
     MainMenu { menu_selection : gui::MainMenuSelection },
+    EnterSeed { seed : u64 },
     SaveGame,
     GameOver,
     ShowRemoveItem,
-    GameWon
+    ShowPowerConsole,
+    GameWon,
+    NextDeck,
+    PreviousDeck
  }
 
 pub struct State {
@@ -68,28 +103,48 @@ impl State {
     fn run_systems(&mut self) {
         let mut vis = VisibilitySystem{};
         vis.run_now(&self.ecs);
+        let mut initiative = InitiativeSystem{};
+        initiative.run_now(&self.ecs);
         let mut mob = RobotAI{};
         mob.run_now(&self.ecs);
         let mut mapindex = MapIndexingSystem{};
         mapindex.run_now(&self.ecs);
+        let mut alarm = AlarmSystem{};
+        alarm.run_now(&self.ecs);
         let mut melee = MeleeCombatSystem{};
         melee.run_now(&self.ecs);
         let mut ranged_combat_system = RangedCombatSystem{};
         ranged_combat_system.run_now(&self.ecs);
+        let mut noise = NoiseSystem{};
+        noise.run_now(&self.ecs);
         let mut damage = DamageSystem{};
         damage.run_now(&self.ecs);
         let mut energy = EnergySystem{};
         energy.run_now(&self.ecs);
+        let mut stamina = StaminaSystem{};
+        stamina.run_now(&self.ecs);
+        let mut power = PowerSystem{};
+        power.run_now(&self.ecs);
+        let mut atmosphere = AtmosphereSystem{};
+        atmosphere.run_now(&self.ecs);
+        let mut heat = HeatSystem{};
+        heat.run_now(&self.ecs);
         let mut oxygen = OxygenSystem{};
         oxygen.run_now(&self.ecs);
+        let mut suit_heat = SuitHeatSystem{};
+        suit_heat.run_now(&self.ecs);
         let mut pickup = ItemCollectionSystem{};
         pickup.run_now(&self.ecs);
         let mut item_use = ItemUseSystem{};
         item_use.run_now(&self.ecs);
+        let mut hacking = HackingSystem{};
+        hacking.run_now(&self.ecs);
         let mut drop_items = ItemDropSystem{};
         drop_items.run_now(&self.ecs);
         let mut item_remove = ItemRemoveSystem{};
         item_remove.run_now(&self.ecs);
+        let mut equipment = EquipmentSystem{};
+        equipment.run_now(&self.ecs);
         self.ecs.maintain();
 
     }
@@ -106,7 +161,7 @@ impl GameState for State {
         ctx.cls();
 
         match new_run_state {
-            RunState::MainMenu{..} => {}
+            RunState::MainMenu{..} | RunState::EnterSeed{..} => {}
             _ => {
 
                 camera::render_camera(&self.ecs, ctx);
@@ -123,9 +178,12 @@ impl GameState for State {
                     gui::MainMenuResult::Selected{ selected } => {
                         match selected {
                             gui::MainMenuSelection::NewGame => {
-                                self.game_over_cleanup();
+                                self.new_game(game_seed::random_seed());
                                 new_run_state = RunState::PreRun;
                             }
+                            gui::MainMenuSelection::EnterSeed => {
+                                new_run_state = RunState::EnterSeed{ seed: 0 };
+                            }
                             gui::MainMenuSelection::LoadGame => {
                                 saveload_system::load_game(&mut self.ecs);
                                 new_run_state = RunState::AwaitingInput;
@@ -136,6 +194,17 @@ impl GameState for State {
                     }
                 }
             }
+            RunState::EnterSeed{ .. } => {
+                let result = gui::seed_entry(self, ctx);
+                match result {
+                    gui::SeedEntryResult::Cancel => new_run_state = RunState::MainMenu{ menu_selection: gui::MainMenuSelection::EnterSeed },
+                    gui::SeedEntryResult::NoResponse{ seed } => new_run_state = RunState::EnterSeed{ seed },
+                    gui::SeedEntryResult::Selected{ seed } => {
+                        self.new_game(seed);
+                        new_run_state = RunState::PreRun;
+                    }
+                }
+            }
             RunState::SaveGame => {
                 saveload_system::save_game(&mut self.ecs);
                 new_run_state = RunState::MainMenu{ menu_selection : gui::MainMenuSelection::LoadGame };
@@ -149,16 +218,21 @@ impl GameState for State {
                 new_run_state = player_input(self, ctx);
             }
             RunState::PlayerTurn => {
-                player::end_turn_breathing(&mut self.ecs);
+                self.ecs.write_resource::<GameSeed>().turn += 1;
                 self.run_systems();
                 player::end_turn_targeting(&mut self.ecs);
                 self.ecs.maintain();
-                new_run_state = RunState::MonsterTurn;
+                new_run_state = initiative_system::after_player_turn(&self.ecs);
             }
             RunState::MonsterTurn => {
                 self.run_systems();
+                // Fabricators only finish a build when game time moves on
+                let ticked = self.ecs.fetch::<GameClock>().ticked;
+                if ticked {
+                    alarm_system::fabricate_robots(&mut self.ecs);
+                }
                 self.ecs.maintain();
-                new_run_state = RunState::AwaitingInput;
+                new_run_state = initiative_system::after_monster_turn(&self.ecs);
             }
             RunState::ShowInventory => {
                 let result = gui::show_inventory(self, ctx);
@@ -205,8 +279,19 @@ impl GameState for State {
                     }
                 }
             }
+            RunState::ShowPowerConsole => {
+                let result = gui::power_console(self, ctx);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        power_system::toggle_subsystem(&mut self.ecs, result.1.unwrap());
+                        new_run_state = RunState::PlayerTurn;
+                    }
+                }
+            }
             RunState::ShowTargeting{range, item} => {
-                let result = gui::ranged_target(self, ctx, range);
+                let result = gui::ranged_target(self, ctx, range, FiringPattern::Single);
                 match result.0 {
                     gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
                     gui::ItemMenuResult::NoResponse => {}
@@ -227,6 +312,14 @@ impl GameState for State {
                     }
                 }
             }
+            RunState::NextDeck => {
+                self.goto_deck(1);
+                new_run_state = RunState::PreRun;
+            }
+            RunState::PreviousDeck => {
+                self.goto_deck(-1);
+                new_run_state = RunState::PreRun;
+            }
             RunState::GameWon => {
                 let result = gui::game_won(ctx);
                 match result {
@@ -250,6 +343,14 @@ impl GameState for State {
 }
 
 impl State {
+    fn new_game(&mut self, seed: u64) {
+        {
+            let mut game_seed = self.ecs.write_resource::<GameSeed>();
+            *game_seed = GameSeed{ seed, turn: 0 };
+        }
+        self.game_over_cleanup();
+    }
+
     fn game_over_cleanup(&mut self) {
         // Delete everything
         let mut to_delete = Vec::new();
@@ -260,24 +361,21 @@ impl State {
             self.ecs.delete_entity(*del).expect("Deletion failed");
         }
     
-        // Build a new map and place the player
-        let map;
+        // Reseed the generator so the same seed always builds the same wreck
         {
-            let mut map_ressource = self.ecs.write_resource::<Map>();
-            *map_ressource = Map::new_map_rooms_and_corridors();
-            map = map_ressource.clone();
-        }
-    
-        // Spawn bad guys
-        for room in map.rooms.iter().skip(1) {
-            spawner::spawn_room(&mut self.ecs, room);
+            let mut game_seed = self.ecs.write_resource::<GameSeed>();
+            game_seed.turn = 0;
+            let mut rng = self.ecs.write_resource::<RandomNumberGenerator>();
+            *rng = RandomNumberGenerator::seeded(game_seed.seed);
+            let mut decks = self.ecs.write_resource::<DeckMaster>();
+            *decks = DeckMaster::new();
         }
-    
-        // Place the player and update resources
-        let (player_x, player_y) = map.rooms[0].center();
+
+        // Build the top deck and place the player
+        let player_start = self.generate_deck(1);
+        let (player_x, player_y) = (player_start.x, player_start.y);
         let player_entity = spawner::player(&mut self.ecs, player_x, player_y);
         self.spawn_start_inventory(player_x, player_y);
-        self.spawn_artefact_of_yendoria(map);
         let mut player_position = self.ecs.write_resource::<Point>();
         *player_position = Point::new(player_x, player_y);
         let mut position_components = self.ecs.write_storage::<Position>();
@@ -301,15 +399,205 @@ impl State {
     }
 
     fn spawn_start_inventory(&mut self, player_x: i32, player_y: i32) {
-        spawner::blaster(&mut self.ecs, player_x + 1, player_y);
-        spawner::battery(&mut self.ecs, player_x + 2, player_y);
-        spawner::stim_packs(&mut self.ecs, player_x + 2, player_y + 1);
-        spawner::oxygen_tank(&mut self.ecs, player_x + 2, player_y + 2);
+        spawner::spawn_named_item(&mut self.ecs, "Blaster", player_x + 1, player_y);
+        spawner::spawn_named_item(&mut self.ecs, "Battery", player_x + 2, player_y);
+        spawner::spawn_named_item(&mut self.ecs, "Basic Stim Pack", player_x + 2, player_y + 1);
+        spawner::spawn_named_item(&mut self.ecs, "Oxygen Tank", player_x + 2, player_y + 2);
     }
 
-    fn spawn_artefact_of_yendoria(&mut self, map: Map) {
-        let (artefact_x, artefact_y) = map.rooms.last().unwrap().center();
-        spawner::artefact(&mut self.ecs, artefact_x, artefact_y);
+    fn generate_deck(&mut self, depth: i32) -> Position {
+        let mut map;
+        let player_start;
+        let artefact_position;
+        {
+            let mut rng = self.ecs.write_resource::<RandomNumberGenerator>();
+            let mut builder = map_builders::random_builder(depth, &mut rng);
+            builder.build_map(&mut rng);
+            map = builder.get_map();
+            player_start = builder.get_starting_position();
+            artefact_position = builder.get_artefact_position();
+        }
+
+        // The artefact waits on the lowest deck, every other deck has a lift further down
+        if depth > 1 {
+            let idx = map.xy_idx(player_start.x, player_start.y);
+            map.tiles[idx] = TileType::LiftUp;
+        }
+        if depth < DECK_COUNT {
+            let idx = map.xy_idx(artefact_position.x, artefact_position.y);
+            map.tiles[idx] = TileType::LiftDown;
+        }
+        power_system::install_console(&mut map, player_start.x, player_start.y);
+        // The deck's fabricators are still running when the salvager boards
+        map.power.fabrication = true;
+        {
+            let mut rng = self.ecs.write_resource::<RandomNumberGenerator>();
+            atmosphere_system::pressurize_compartments(&mut map, &mut rng);
+            room_types::assign_room_types(&mut map, &mut rng);
+            heat_system::place_heat_sources(&mut map, &mut rng);
+            robot_ai_system::install_repair_station(&mut map, &mut rng);
+        }
+        {
+            let mut map_ressource = self.ecs.write_resource::<Map>();
+            *map_ressource = map.clone();
+        }
+
+        // Furnish the rooms, then spawn bad guys
+        for (room, room_type) in map.rooms.iter().zip(map.room_types.iter()) {
+            spawner::furnish_room(&mut self.ecs, room, *room_type);
+        }
+        for (room, room_type) in map.rooms.iter().zip(map.room_types.iter()).skip(1) {
+            spawner::spawn_room(&mut self.ecs, room, *room_type, depth);
+        }
+        spawner::spawn_power_cores(&mut self.ecs, &map.rooms);
+        spawner::spawn_security(&mut self.ecs, &map.rooms, depth);
+        if depth == DECK_COUNT {
+            self.spawn_artefact_of_yendoria(artefact_position);
+        }
+
+        player_start
+    }
+
+    fn goto_deck(&mut self, offset: i32) {
+        let current_depth = self.ecs.fetch::<Map>().depth;
+        let new_depth = current_depth + offset;
+        self.freeze_deck_entities(current_depth);
+        {
+            let map = self.ecs.fetch::<Map>().clone();
+            let mut decks = self.ecs.write_resource::<DeckMaster>();
+            decks.store_map(&map);
+        }
+
+        // Come back to a deck exactly as it was left, or build a fresh one
+        let stored_map = self.ecs.fetch::<DeckMaster>().get_map(new_depth);
+        let player_start;
+        if let Some(map) = stored_map {
+            let arrival_tile = if offset > 0 { TileType::LiftUp } else { TileType::LiftDown };
+            let idx = map.tiles.iter().position(|tile| *tile == arrival_tile).unwrap_or(0) as i32;
+            player_start = Position{ x: idx % map.width, y: idx / map.width };
+            *self.ecs.write_resource::<Map>() = map;
+            self.thaw_deck_entities(new_depth);
+        } else {
+            player_start = self.generate_deck(new_depth);
+        }
+        self.bring_companions(&player_start, current_depth);
+
+        // Place the player and update resources
+        let player_entity = *self.ecs.fetch::<Entity>();
+        let mut player_position = self.ecs.write_resource::<Point>();
+        *player_position = Point::new(player_start.x, player_start.y);
+        let mut position_components = self.ecs.write_storage::<Position>();
+        position_components.insert(player_entity, player_start).expect("Unable to insert position");
+
+        // Mark the player's visibility as dirty
+        let mut viewshed_components = self.ecs.write_storage::<Viewshed>();
+        let vs = viewshed_components.get_mut(player_entity);
+        if let Some(vs) = vs {
+            vs.dirty = true;
+        }
+
+        // Targets left behind can't be shot at any more
+        self.ecs.write_storage::<Target>().clear();
+
+        let mut log = self.ecs.fetch_mut::<GameLog>();
+        if offset > 0 {
+            log.entries.push(format!("You take the lift down to deck {}.", new_depth));
+        } else {
+            log.entries.push(format!("You take the lift up to deck {}.", new_depth));
+        }
+    }
+
+    fn freeze_deck_entities(&mut self, depth: i32) {
+        let entities = self.ecs.entities();
+        let player_entity = self.ecs.fetch::<Entity>();
+        let companions = self.ecs.read_storage::<Companion>();
+        let immobile = self.ecs.read_storage::<Immobile>();
+        let mut positions = self.ecs.write_storage::<Position>();
+        let mut other_deck_positions = self.ecs.write_storage::<OtherDeckPosition>();
+
+        let mut frozen : Vec<Entity> = Vec::new();
+        for (entity, pos) in (&entities, &positions).join() {
+            // Companions that can walk take the lift along with the player
+            let follows = companions.get(entity).is_some() && immobile.get(entity).is_none();
+            if entity != *player_entity && !follows {
+                other_deck_positions.insert(entity, OtherDeckPosition{ x: pos.x, y: pos.y, depth }).expect("Unable to insert position");
+                frozen.push(entity);
+            }
+        }
+        for entity in frozen.iter() {
+            positions.remove(*entity);
+        }
+    }
+
+    // Puts the companions that came along on free floor next to the arrival lift, any without room stay on the old deck
+    fn bring_companions(&mut self, arrival: &Position, old_depth: i32) {
+        let entities = self.ecs.entities();
+        let map = self.ecs.fetch::<Map>();
+        let companions = self.ecs.read_storage::<Companion>();
+        let mut positions = self.ecs.write_storage::<Position>();
+        let mut other_deck_positions = self.ecs.write_storage::<OtherDeckPosition>();
+        let mut viewsheds = self.ecs.write_storage::<Viewshed>();
+
+        let followers : Vec<Entity> = (&entities, &companions, &positions).join()
+            .map(|(entity, _companion, _pos)| entity)
+            .collect();
+        if followers.is_empty() { return; }
+
+        let mut taken : Vec<(i32, i32)> = (&entities, &positions).join()
+            .filter(|(entity, _pos)| !followers.contains(entity))
+            .map(|(_entity, pos)| (pos.x, pos.y))
+            .collect();
+        taken.push((arrival.x, arrival.y));
+
+        let mut spots : Vec<(i32, i32)> = Vec::new();
+        for y in arrival.y - 3 ..= arrival.y + 3 {
+            for x in arrival.x - 3 ..= arrival.x + 3 {
+                if x < 0 || x >= map.width || y < 0 || y >= map.height { continue; }
+                if map.tiles[map.xy_idx(x, y)] == TileType::Floor {
+                    spots.push((x, y));
+                }
+            }
+        }
+        spots.sort_by_key(|(x, y)| (x - arrival.x).abs().max((y - arrival.y).abs()));
+
+        for follower in followers {
+            let spot = spots.iter().find(|spot| !taken.contains(spot)).copied();
+            if let Some((x, y)) = spot {
+                taken.push((x, y));
+                positions.insert(follower, Position{ x, y }).expect("Unable to insert position");
+                if let Some(viewshed) = viewsheds.get_mut(follower) {
+                    viewshed.dirty = true;
+                }
+            } else if let Some(pos) = positions.get(follower).cloned() {
+                other_deck_positions.insert(follower, OtherDeckPosition{ x: pos.x, y: pos.y, depth: old_depth }).expect("Unable to insert position");
+                positions.remove(follower);
+            }
+        }
+    }
+
+    fn thaw_deck_entities(&mut self, depth: i32) {
+        let entities = self.ecs.entities();
+        let mut positions = self.ecs.write_storage::<Position>();
+        let mut other_deck_positions = self.ecs.write_storage::<OtherDeckPosition>();
+        let mut viewsheds = self.ecs.write_storage::<Viewshed>();
+
+        let mut thawed : Vec<Entity> = Vec::new();
+        for (entity, pos) in (&entities, &other_deck_positions).join() {
+            if pos.depth == depth {
+                positions.insert(entity, Position{ x: pos.x, y: pos.y }).expect("Unable to insert position");
+                if let Some(viewshed) = viewsheds.get_mut(entity) {
+                    viewshed.dirty = true;
+                }
+                thawed.push(entity);
+            }
+        }
+        for entity in thawed.iter() {
+            other_deck_positions.remove(*entity);
+        }
+    }
+
+    fn spawn_artefact_of_yendoria(&mut self, artefact_position: Position) {
+        spawner::spawn_named_item(&mut self.ecs, "Artefact from Yendoria", artefact_position.x, artefact_position.y);
     }
 }
 
@@ -360,27 +648,50 @@ fn main() -> rltk::BError{
     gs.ecs.register::<ProvidesOxygen>();
     gs.ecs.register::<ProvidesEnergy>();
     gs.ecs.register::<ArtefactFromYendoria>();
+    gs.ecs.register::<OtherDeckPosition>();
+    gs.ecs.register::<CanOpenDoors>();
+    gs.ecs.register::<PowerCore>();
+    gs.ecs.register::<BaseStats>();
+    gs.ecs.register::<PoolBonus>();
+    gs.ecs.register::<DefenseBonus>();
+    gs.ecs.register::<ViewRangeBonus>();
+    gs.ecs.register::<OxygenEfficiency>();
+    gs.ecs.register::<Exertion>();
+    gs.ecs.register::<WantsToRest>();
+    gs.ecs.register::<ThermalRegulator>();
+    gs.ecs.register::<Immobile>();
+    gs.ecs.register::<Resistances>();
+    gs.ecs.register::<Magazine>();
+    gs.ecs.register::<AmmoPack>();
+    gs.ecs.register::<Aiming>();
+    gs.ecs.register::<LootTable>();
+    gs.ecs.register::<Wreckage>();
+    gs.ecs.register::<Brain>();
+    gs.ecs.register::<MakesNoise>();
+    gs.ecs.register::<Faction>();
+    gs.ecs.register::<Initiative>();
+    gs.ecs.register::<TimeCost>();
+    gs.ecs.register::<Companion>();
+    gs.ecs.register::<WantsToHack>();
+    gs.ecs.register::<HackingTool>();
+    gs.ecs.register::<SecurityCamera>();
+    gs.ecs.register::<Fabricator>();
 
     gs.ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
-    
-    let map : Map = Map::new_map_rooms_and_corridors();
-    let (player_x, player_y) = map.rooms[0].center();
-    let player_entity = spawner::player(&mut gs.ecs, player_x, player_y);
+    gs.ecs.insert(raws::load_raws());
 
+    let seed = game_seed::random_seed();
+    gs.ecs.insert(RandomNumberGenerator::seeded(seed));
+    gs.ecs.insert(GameSeed{ seed, turn: 0 });
+    gs.ecs.insert(GameClock::default());
+    gs.ecs.insert(DeckMaster::new());
+    gs.ecs.insert(Map::new("The wreck", 1));
 
-    gs.ecs.insert(rltk::RandomNumberGenerator::new());
-    for room in map.rooms.iter().skip(1) {
-        spawner::spawn_room(&mut gs.ecs, room);
-    }
-
-    spawner::blaster(&mut gs.ecs, player_x + 1, player_y);
-    spawner::battery(&mut gs.ecs, player_x + 2, player_y);
-    spawner::stim_packs(&mut gs.ecs, player_x + 2, player_y + 1);
-    spawner::oxygen_tank(&mut gs.ecs, player_x + 2, player_y + 2);
-    let (artefact_x, artefact_y) = map.rooms.last().unwrap().center();
-    spawner::artefact(&mut gs.ecs, artefact_x, artefact_y);
+    let player_start = gs.generate_deck(1);
+    let (player_x, player_y) = (player_start.x, player_start.y);
+    let player_entity = spawner::player(&mut gs.ecs, player_x, player_y);
+    gs.spawn_start_inventory(player_x, player_y);
 
-    gs.ecs.insert(map);
     gs.ecs.insert(Point::new(player_x, player_y));
     gs.ecs.insert(player_entity);
     gs.ecs.insert(RunState::MainMenu{menu_selection: MainMenuSelection::NewGame });
@@ -2,11 +2,10 @@ use rltk::BaseMap;
 use rltk::Algorithm2D;
 use rltk::Point;
 use specs::prelude::*;
-use rltk::RandomNumberGenerator;
-use std::cmp::max;
-use std::cmp::min;
 use rltk::{ RGB, Rltk };
 use super::{Rect};
+use crate::power_system::PowerGrid;
+use crate::room_types::RoomType;
 use serde::{Serialize, Deserialize};
 
 pub const MAP_WIDTH : usize = 48;
@@ -15,19 +14,36 @@ pub const MAP_COUNT : usize = MAP_WIDTH * MAP_HEIGHT;
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum TileType {
-    Wall, Floor
+    Wall, Floor, LiftUp, LiftDown, DoorClosed, DoorOpen, Console, RepairStation
+}
+
+impl TileType {
+    // Walls, closed doors and the consoles set into walls stop movement, sight, air, shots and noise alike
+    pub fn is_solid(self) -> bool {
+        matches!(self, TileType::Wall | TileType::DoorClosed | TileType::Console)
+    }
 }
 
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct Map {
     pub tiles : Vec<TileType>,
     pub rooms : Vec<Rect>,
+    pub room_types : Vec<RoomType>,
     pub width : i32,
     pub height : i32,
     pub revealed_tiles : Vec<bool>,
     pub visible_tiles : Vec<bool>,
     pub blocked_tiles : Vec<bool>,
+    pub atmosphere : Vec<i32>,
+    pub breaches : Vec<bool>,
+    pub temperature : Vec<i32>,
+    pub heat_sources : Vec<i32>,
+    pub power : PowerGrid,
+    pub alarm : i32,
+    // Where the cameras last saw the salvager, fabricated robots start their search there
+    pub alarm_spot : Option<Point>,
     pub name: String,
+    pub depth: i32,
     
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
@@ -40,31 +56,12 @@ impl Map {
         (y as usize * self.width as usize) + x as usize
     }
 
-    fn apply_room_to_map(&mut self, room : &Rect) {
-        for y in room.y1 +1 ..= room.y2 {
-            for x in room.x1 + 1 ..= room.x2 {
-                let idx = self.xy_idx(x, y);
-                self.tiles[idx] = TileType::Floor;
-            }
-        }
+    pub fn room_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.rooms.iter().position(|room| room.contains(x, y))
     }
 
-    fn apply_horizontal_tunnel(&mut self, x1:i32, x2:i32, y:i32) {
-        for x in min(x1,x2) ..= max(x1,x2) {
-            let idx = self.xy_idx(x, y);
-            if idx > 0 && idx < (self.width*self.height) as usize {
-                self.tiles[idx as usize] = TileType::Floor;
-            }
-        }
-    }
-    
-    fn apply_vertical_tunnel(&mut self, y1:i32, y2:i32, x:i32) {
-        for y in min(y1,y2) ..= max(y1,y2) {
-            let idx = self.xy_idx(x, y);
-            if idx > 0 && idx < (self.width*self.height) as usize {
-                self.tiles[idx as usize] = TileType::Floor;
-            }
-        }
+    pub fn room_type_at(&self, x: i32, y: i32) -> Option<RoomType> {
+        self.room_at(x, y).and_then(|i| self.room_types.get(i).copied())
     }
 
     fn is_exit_valid(&self, x:i32, y:i32) -> bool {
@@ -75,7 +72,7 @@ impl Map {
 
     pub fn populate_blocked(&mut self) {
         for (i,tile) in self.tiles.iter_mut().enumerate() {
-            self.blocked_tiles[i] = *tile == TileType::Wall;
+            self.blocked_tiles[i] = tile.is_solid();
         }
     }
 
@@ -85,67 +82,27 @@ impl Map {
         }
     }
 
-    pub fn new_map_rooms_and_corridors() -> Map {
-        let mut map = Map{
+    pub fn new<S : ToString>(name : S, depth : i32) -> Map {
+        Map{
             tiles : vec![TileType::Wall; MAP_COUNT],
             rooms : Vec::new(),
+            room_types : Vec::new(),
             width : MAP_WIDTH as i32,
             height: MAP_HEIGHT as i32,
             revealed_tiles : vec![false; MAP_COUNT],
             visible_tiles : vec![false; MAP_COUNT],
             blocked_tiles : vec![false; MAP_COUNT],
+            atmosphere : vec![0; MAP_COUNT],
+            breaches : vec![false; MAP_COUNT],
+            temperature : vec![0; MAP_COUNT],
+            heat_sources : vec![0; MAP_COUNT],
+            power : PowerGrid::default(),
+            alarm : 0,
+            alarm_spot : None,
             tile_content : vec![Vec::new(); MAP_COUNT],
-            name : "The wreck".to_string()
-        };
-    
-        const MAX_ROOMS : i32 = 30;
-        const MIN_SIZE : i32 = 6;
-        const MAX_SIZE : i32 = 10;
-    
-        let mut rng = RandomNumberGenerator::new();
-    
-        for i in 0..MAX_ROOMS {
-            let room_width;
-            let room_height;
-            let x;
-            let y;
-            if i == 0{
-                room_width = rng.range(MIN_SIZE, MAX_SIZE);
-                room_height = rng.range(MIN_SIZE, MAX_SIZE);
-                x = 1;
-                y = 1;
-            }else{
-                room_width = rng.range(MIN_SIZE, MAX_SIZE);
-                room_height = rng.range(MIN_SIZE, MAX_SIZE);
-                x = rng.roll_dice(1, map.width - room_width - 1) -1;
-                y = rng.roll_dice(1, map.height - room_height - 1) -1;
-
-            }
-            let new_room = Rect::new(x, y, room_width, room_height);
-            let mut ok = true;
-            for other_room in map.rooms.iter() {
-                if new_room.intersect(other_room) { ok = false}
-            }
-            if ok {
-                map.apply_room_to_map(&new_room);
-    
-                if !map.rooms.is_empty() {
-                    let (new_x, new_y) = new_room.center();
-                    let (prev_x, prev_y) = map.rooms[map.rooms.len()-1].center();
-                    if rng.range(0,2) == 1 {
-                        map.apply_horizontal_tunnel(prev_x, new_x, prev_y);
-                        map.apply_vertical_tunnel(prev_y, new_y, new_x);
-                    } else {
-                        map.apply_vertical_tunnel(prev_y, new_y, prev_x);
-                        map.apply_horizontal_tunnel(prev_x, new_x, new_y);
-                    }
-                }
-    
-                map.rooms.push(new_room);
-            }
+            name : name.to_string(),
+            depth
         }
-    
-        map
     }
 }
 
@@ -157,7 +114,7 @@ impl Algorithm2D for Map {
 
 impl BaseMap for Map {
     fn is_opaque(&self, idx:usize) -> bool {
-        self.tiles[idx as usize] == TileType::Wall
+        self.tiles[idx].is_solid()
     }
     fn get_available_exits(&self, idx:usize) -> rltk::SmallVec<[(usize, f32); 10]> {
         let mut exits = rltk::SmallVec::new();
@@ -206,6 +163,30 @@ pub fn draw_map(ecs: &World, ctx : &mut Rltk) {
                     glyph = rltk::to_cp437('#');
                     fg = RGB::from_f32(0.0, 1.0, 0.0);
                 }
+                TileType::LiftUp => {
+                    glyph = rltk::to_cp437('<');
+                    fg = RGB::from_f32(0.0, 1.0, 1.0);
+                }
+                TileType::LiftDown => {
+                    glyph = rltk::to_cp437('>');
+                    fg = RGB::from_f32(0.0, 1.0, 1.0);
+                }
+                TileType::DoorClosed => {
+                    glyph = rltk::to_cp437('+');
+                    fg = RGB::from_f32(1.0, 0.5, 0.0);
+                }
+                TileType::DoorOpen => {
+                    glyph = rltk::to_cp437('/');
+                    fg = RGB::from_f32(1.0, 0.5, 0.0);
+                }
+                TileType::Console => {
+                    glyph = rltk::to_cp437('≡');
+                    fg = RGB::from_f32(1.0, 1.0, 0.0);
+                }
+                TileType::RepairStation => {
+                    glyph = rltk::to_cp437('⌂');
+                    fg = RGB::from_f32(0.0, 0.5, 1.0);
+                }
             }
             if !map.visible_tiles[idx] { fg = fg.to_greyscale() }
             ctx.set(x, y, fg, RGB::from_f32(0.,0.,0.), glyph);
@@ -0,0 +1,89 @@
+use super::{MapBuilder, Map, Position, apply_room_to_map, draw_corridor, add_doors};
+use super::super::Rect;
+use rltk::RandomNumberGenerator;
+
+const MIN_ROOM_SIZE : i32 = 6;
+
+pub struct BspInteriorBuilder {
+    map : Map,
+    starting_position : Position,
+    artefact_position : Position
+}
+
+impl MapBuilder for BspInteriorBuilder {
+    fn build_map(&mut self, rng : &mut RandomNumberGenerator) {
+        // Subdivide the whole hull into compartments
+        let hull = Rect::new(1, 1, self.map.width - 3, self.map.height - 3);
+        self.subdivide(hull, rng);
+
+        let rooms = self.map.rooms.clone();
+        for room in rooms.iter() {
+            apply_room_to_map(&mut self.map, room);
+        }
+
+        // Neighbouring compartments are connected by a passage
+        for i in 0..rooms.len()-1 {
+            let room = rooms[i];
+            let next_room = rooms[i+1];
+            let start_x = room.x1 + rng.roll_dice(1, i32::abs(room.x1 - room.x2));
+            let start_y = room.y1 + rng.roll_dice(1, i32::abs(room.y1 - room.y2));
+            let end_x = next_room.x1 + rng.roll_dice(1, i32::abs(next_room.x1 - next_room.x2));
+            let end_y = next_room.y1 + rng.roll_dice(1, i32::abs(next_room.y1 - next_room.y2));
+            draw_corridor(&mut self.map, start_x, start_y, end_x, end_y);
+        }
+
+        // Ship compartments are sealed off by bulkhead doors
+        add_doors(&mut self.map, rng, 100);
+
+        let (start_x, start_y) = rooms[0].center();
+        self.starting_position = Position{ x: start_x, y: start_y };
+        let (artefact_x, artefact_y) = rooms.last().unwrap().center();
+        self.artefact_position = Position{ x: artefact_x, y: artefact_y };
+    }
+
+    fn get_map(&self) -> Map {
+        self.map.clone()
+    }
+
+    fn get_starting_position(&self) -> Position {
+        self.starting_position.clone()
+    }
+
+    fn get_artefact_position(&self) -> Position {
+        self.artefact_position.clone()
+    }
+}
+
+impl BspInteriorBuilder {
+    pub fn new(new_depth : i32) -> BspInteriorBuilder {
+        BspInteriorBuilder{
+            map : Map::new("Derelict freighter", new_depth),
+            starting_position : Position{ x: 0, y: 0 },
+            artefact_position : Position{ x: 0, y: 0 }
+        }
+    }
+
+    fn subdivide(&mut self, rect : Rect, rng : &mut RandomNumberGenerator) {
+        let width = rect.x2 - rect.x1;
+        let height = rect.y2 - rect.y1;
+        let can_split_x = width > MIN_ROOM_SIZE * 2;
+        let can_split_y = height > MIN_ROOM_SIZE * 2;
+
+        // Leave some compartments large so the ship doesn't become a grid of closets
+        if (!can_split_x && !can_split_y) || (width < MIN_ROOM_SIZE * 3 && height < MIN_ROOM_SIZE * 3 && rng.roll_dice(1, 4) == 1) {
+            self.map.rooms.push(rect);
+            return;
+        }
+
+        // Each half loses one tile at the split line, which becomes the bulkhead between them
+        if can_split_x && (!can_split_y || rng.roll_dice(1, 2) == 1) {
+            let split = rng.range(MIN_ROOM_SIZE, width - MIN_ROOM_SIZE + 1);
+            self.subdivide(Rect::new(rect.x1, rect.y1, split - 1, height), rng);
+            self.subdivide(Rect::new(rect.x1 + split, rect.y1, width - split, height), rng);
+        } else {
+            let split = rng.range(MIN_ROOM_SIZE, height - MIN_ROOM_SIZE + 1);
+            self.subdivide(Rect::new(rect.x1, rect.y1, width, split - 1), rng);
+            self.subdivide(Rect::new(rect.x1, rect.y1 + split, width, height - split), rng);
+        }
+    }
+}
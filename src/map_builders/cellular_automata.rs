@@ -0,0 +1,86 @@
+use super::{MapBuilder, Map, Position, clear_docking_area, cull_unreachable_and_find_farthest, rooms_from_regions};
+use super::super::TileType;
+use rltk::RandomNumberGenerator;
+
+pub struct CellularAutomataBuilder {
+    map : Map,
+    starting_position : Position,
+    artefact_position : Position
+}
+
+impl MapBuilder for CellularAutomataBuilder {
+    fn build_map(&mut self, rng : &mut RandomNumberGenerator) {
+        // Start with random rock, roughly 55% of it open
+        for y in 1..self.map.height-1 {
+            for x in 1..self.map.width-1 {
+                let roll = rng.roll_dice(1, 100);
+                let idx = self.map.xy_idx(x, y);
+                if roll > 55 { self.map.tiles[idx] = TileType::Floor }
+                else { self.map.tiles[idx] = TileType::Wall }
+            }
+        }
+
+        // Let the rock settle into caves
+        for _i in 0..15 {
+            let mut newtiles = self.map.tiles.clone();
+
+            for y in 1..self.map.height-1 {
+                for x in 1..self.map.width-1 {
+                    let idx = self.map.xy_idx(x, y);
+                    let w = self.map.width as usize;
+                    let mut neighbors = 0;
+                    if self.map.tiles[idx - 1] == TileType::Wall { neighbors += 1; }
+                    if self.map.tiles[idx + 1] == TileType::Wall { neighbors += 1; }
+                    if self.map.tiles[idx - w] == TileType::Wall { neighbors += 1; }
+                    if self.map.tiles[idx + w] == TileType::Wall { neighbors += 1; }
+                    if self.map.tiles[idx - (w - 1)] == TileType::Wall { neighbors += 1; }
+                    if self.map.tiles[idx - (w + 1)] == TileType::Wall { neighbors += 1; }
+                    if self.map.tiles[idx + (w - 1)] == TileType::Wall { neighbors += 1; }
+                    if self.map.tiles[idx + (w + 1)] == TileType::Wall { neighbors += 1; }
+
+                    if neighbors > 4 || neighbors == 0 {
+                        newtiles[idx] = TileType::Wall;
+                    }
+                    else {
+                        newtiles[idx] = TileType::Floor;
+                    }
+                }
+            }
+
+            self.map.tiles = newtiles;
+        }
+
+        // Dock at the middle of the asteroid and drop everything the docking spot can't reach
+        let start_x = self.map.width / 2;
+        let start_y = self.map.height / 2;
+        clear_docking_area(&mut self.map, start_x, start_y);
+        self.starting_position = Position{ x: start_x, y: start_y };
+        let start_idx = self.map.xy_idx(start_x, start_y);
+        let artefact_idx = cull_unreachable_and_find_farthest(&mut self.map, start_idx);
+        self.artefact_position = Position{ x: artefact_idx as i32 % self.map.width, y: artefact_idx as i32 / self.map.width };
+
+        self.map.rooms = rooms_from_regions(&self.map, start_x, start_y);
+    }
+
+    fn get_map(&self) -> Map {
+        self.map.clone()
+    }
+
+    fn get_starting_position(&self) -> Position {
+        self.starting_position.clone()
+    }
+
+    fn get_artefact_position(&self) -> Position {
+        self.artefact_position.clone()
+    }
+}
+
+impl CellularAutomataBuilder {
+    pub fn new(new_depth : i32) -> CellularAutomataBuilder {
+        CellularAutomataBuilder{
+            map : Map::new("Asteroid base", new_depth),
+            starting_position : Position{ x: 0, y: 0 },
+            artefact_position : Position{ x: 0, y: 0 }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use super::super::{Map, Rect, TileType};
+use rltk::RandomNumberGenerator;
+use std::cmp::{max, min};
+
+const REGION_SIZE : i32 = 8;
+
+pub fn apply_room_to_map(map : &mut Map, room : &Rect) {
+    for y in room.y1 +1 ..= room.y2 {
+        for x in room.x1 + 1 ..= room.x2 {
+            let idx = map.xy_idx(x, y);
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+}
+
+pub fn apply_horizontal_tunnel(map : &mut Map, x1:i32, x2:i32, y:i32) {
+    for x in min(x1,x2) ..= max(x1,x2) {
+        let idx = map.xy_idx(x, y);
+        if idx > 0 && idx < (map.width*map.height) as usize {
+            map.tiles[idx as usize] = TileType::Floor;
+        }
+    }
+}
+
+pub fn apply_vertical_tunnel(map : &mut Map, y1:i32, y2:i32, x:i32) {
+    for y in min(y1,y2) ..= max(y1,y2) {
+        let idx = map.xy_idx(x, y);
+        if idx > 0 && idx < (map.width*map.height) as usize {
+            map.tiles[idx as usize] = TileType::Floor;
+        }
+    }
+}
+
+pub fn draw_corridor(map : &mut Map, x1:i32, y1:i32, x2:i32, y2:i32) {
+    let mut x = x1;
+    let mut y = y1;
+
+    while x != x2 || y != y2 {
+        if x < x2 {
+            x += 1;
+        } else if x > x2 {
+            x -= 1;
+        } else if y < y2 {
+            y += 1;
+        } else if y > y2 {
+            y -= 1;
+        }
+
+        let idx = map.xy_idx(x, y);
+        map.tiles[idx] = TileType::Floor;
+    }
+}
+
+fn is_door_spot(map : &Map, x:i32, y:i32) -> bool {
+    if x < 1 || x > map.width-2 || y < 1 || y > map.height-2 { return false; }
+    let idx = map.xy_idx(x, y);
+    if map.tiles[idx] != TileType::Floor { return false; }
+
+    // Never put two doors right next to each other
+    for (nx, ny) in [(x-1, y), (x+1, y), (x, y-1), (x, y+1)].iter() {
+        if map.tiles[map.xy_idx(*nx, *ny)] == TileType::DoorClosed { return false; }
+    }
+
+    let wall = |wx:i32, wy:i32| map.tiles[map.xy_idx(wx, wy)] == TileType::Wall;
+    (wall(x-1, y) && wall(x+1, y)) || (wall(x, y-1) && wall(x, y+1))
+}
+
+// Puts a hatch into the openings where passages break through a room's walls
+pub fn add_doors(map : &mut Map, rng : &mut RandomNumberGenerator, chance : i32) {
+    let rooms = map.rooms.clone();
+    for room in rooms.iter() {
+        let mut openings : Vec<(i32, i32)> = Vec::new();
+        for y in room.y1 + 1 ..= room.y2 {
+            openings.push((room.x1, y));
+            openings.push((room.x2 + 1, y));
+        }
+        for x in room.x1 + 1 ..= room.x2 {
+            openings.push((x, room.y1));
+            openings.push((x, room.y2 + 1));
+        }
+
+        for (x, y) in openings.iter() {
+            if is_door_spot(map, *x, *y) && rng.roll_dice(1, 100) <= chance {
+                let idx = map.xy_idx(*x, *y);
+                map.tiles[idx] = TileType::DoorClosed;
+            }
+        }
+    }
+}
+
+// Opens up the spot where the salvager docks, so the starting gear has room next to the player
+pub fn clear_docking_area(map : &mut Map, x:i32, y:i32) {
+    for dy in y-1 ..= y+3 {
+        for dx in x-1 ..= x+3 {
+            if dx > 0 && dx < map.width-1 && dy > 0 && dy < map.height-1 {
+                let idx = map.xy_idx(dx, dy);
+                map.tiles[idx] = TileType::Floor;
+            }
+        }
+    }
+}
+
+// Walls off every floor tile the start can't reach and returns the reachable tile farthest away from it
+pub fn cull_unreachable_and_find_farthest(map : &mut Map, start_idx : usize) -> usize {
+    map.populate_blocked();
+    let map_starts : Vec<usize> = vec![start_idx];
+    let dijkstra_map = rltk::DijkstraMap::new(map.width as usize, map.height as usize, &map_starts, &*map, 1000.0);
+    let mut farthest_tile = start_idx;
+    let mut farthest_distance = 0.0f32;
+    for (i, tile) in map.tiles.iter_mut().enumerate() {
+        if *tile == TileType::Floor {
+            let distance_to_start = dijkstra_map.map[i];
+            if distance_to_start == std::f32::MAX {
+                *tile = TileType::Wall;
+            } else if distance_to_start > farthest_distance {
+                farthest_tile = i;
+                farthest_distance = distance_to_start;
+            }
+        }
+    }
+    map.populate_blocked();
+    farthest_tile
+}
+
+// Open layouts have no real rooms, so carve them into regions to spawn into. The start region always comes first.
+// A room's inside starts one tile past x1/y1, so each region is shifted by one to cover its grid cell without gaps.
+pub fn rooms_from_regions(map : &Map, start_x : i32, start_y : i32) -> Vec<Rect> {
+    let mut rooms : Vec<Rect> = Vec::new();
+    let mut y = 0;
+    while y < map.height {
+        let mut x = 0;
+        while x < map.width {
+            let region = Rect::new(x - 1, y - 1, REGION_SIZE, REGION_SIZE);
+            let mut floor_tiles = 0;
+            for ry in region.y1 + 1 ..= i32::min(region.y2, map.height - 1) {
+                for rx in region.x1 + 1 ..= i32::min(region.x2, map.width - 1) {
+                    if map.tiles[map.xy_idx(rx, ry)] == TileType::Floor {
+                        floor_tiles += 1;
+                    }
+                }
+            }
+            // The docking region is always kept, however little floor it has
+            if region.contains(start_x, start_y) {
+                rooms.insert(0, region);
+            } else if floor_tiles >= REGION_SIZE * 2 {
+                rooms.push(region);
+            }
+            x += REGION_SIZE;
+        }
+        y += REGION_SIZE;
+    }
+    rooms
+}
@@ -0,0 +1,87 @@
+use super::{MapBuilder, Map, Position, clear_docking_area, cull_unreachable_and_find_farthest, rooms_from_regions};
+use super::super::TileType;
+use rltk::RandomNumberGenerator;
+
+const DIGGER_LIFETIME : i32 = 400;
+const FLOOR_PERCENT : f32 = 0.5;
+
+pub struct DrunkardsWalkBuilder {
+    map : Map,
+    starting_position : Position,
+    artefact_position : Position
+}
+
+impl MapBuilder for DrunkardsWalkBuilder {
+    fn build_map(&mut self, rng : &mut RandomNumberGenerator) {
+        let start_x = self.map.width / 2;
+        let start_y = self.map.height / 2;
+        clear_docking_area(&mut self.map, start_x, start_y);
+        self.starting_position = Position{ x: start_x, y: start_y };
+
+        let total_tiles = self.map.width * self.map.height;
+        let desired_floor_tiles = (FLOOR_PERCENT * total_tiles as f32) as usize;
+        let mut floor_tile_count = self.map.tiles.iter().filter(|a| **a == TileType::Floor).count();
+
+        // Mining drills keep boring tunnels from the shaft until enough rock is gone
+        let mut digger_count = 0;
+        while floor_tile_count < desired_floor_tiles && digger_count < 200 {
+            let mut digger_x;
+            let mut digger_y;
+            if digger_count == 0 {
+                digger_x = start_x;
+                digger_y = start_y;
+            } else {
+                digger_x = rng.roll_dice(1, self.map.width - 3) + 1;
+                digger_y = rng.roll_dice(1, self.map.height - 3) + 1;
+                // Only start from a tunnel that already exists
+                if self.map.tiles[self.map.xy_idx(digger_x, digger_y)] != TileType::Floor {
+                    digger_x = start_x;
+                    digger_y = start_y;
+                }
+            }
+
+            for _i in 0..DIGGER_LIFETIME {
+                let idx = self.map.xy_idx(digger_x, digger_y);
+                self.map.tiles[idx] = TileType::Floor;
+
+                match rng.roll_dice(1, 4) {
+                    1 => if digger_x > 2 { digger_x -= 1; }
+                    2 => if digger_x < self.map.width - 2 { digger_x += 1; }
+                    3 => if digger_y > 2 { digger_y -= 1; }
+                    _ => if digger_y < self.map.height - 2 { digger_y += 1; }
+                }
+            }
+
+            digger_count += 1;
+            floor_tile_count = self.map.tiles.iter().filter(|a| **a == TileType::Floor).count();
+        }
+
+        let start_idx = self.map.xy_idx(start_x, start_y);
+        let artefact_idx = cull_unreachable_and_find_farthest(&mut self.map, start_idx);
+        self.artefact_position = Position{ x: artefact_idx as i32 % self.map.width, y: artefact_idx as i32 / self.map.width };
+
+        self.map.rooms = rooms_from_regions(&self.map, start_x, start_y);
+    }
+
+    fn get_map(&self) -> Map {
+        self.map.clone()
+    }
+
+    fn get_starting_position(&self) -> Position {
+        self.starting_position.clone()
+    }
+
+    fn get_artefact_position(&self) -> Position {
+        self.artefact_position.clone()
+    }
+}
+
+impl DrunkardsWalkBuilder {
+    pub fn new(new_depth : i32) -> DrunkardsWalkBuilder {
+        DrunkardsWalkBuilder{
+            map : Map::new("Mining tunnels", new_depth),
+            starting_position : Position{ x: 0, y: 0 },
+            artefact_position : Position{ x: 0, y: 0 }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::{Map, Position};
+use rltk::RandomNumberGenerator;
+mod common;
+use common::*;
+mod rooms_and_corridors;
+use rooms_and_corridors::RoomsAndCorridorsBuilder;
+mod bsp_interior;
+use bsp_interior::BspInteriorBuilder;
+mod cellular_automata;
+use cellular_automata::CellularAutomataBuilder;
+mod drunkards_walk;
+use drunkards_walk::DrunkardsWalkBuilder;
+
+pub trait MapBuilder {
+    fn build_map(&mut self, rng : &mut RandomNumberGenerator);
+    fn get_map(&self) -> Map;
+    fn get_starting_position(&self) -> Position;
+    fn get_artefact_position(&self) -> Position;
+}
+
+// Every mission docks at a different kind of wreck
+pub fn random_builder(new_depth : i32, rng : &mut RandomNumberGenerator) -> Box<dyn MapBuilder> {
+    match rng.roll_dice(1, 4) {
+        1 => Box::new(BspInteriorBuilder::new(new_depth)),
+        2 => Box::new(CellularAutomataBuilder::new(new_depth)),
+        3 => Box::new(DrunkardsWalkBuilder::new(new_depth)),
+        _ => Box::new(RoomsAndCorridorsBuilder::new(new_depth))
+    }
+}
@@ -0,0 +1,87 @@
+use super::{MapBuilder, Map, Position, apply_room_to_map, apply_horizontal_tunnel, apply_vertical_tunnel, add_doors};
+use super::super::Rect;
+use rltk::RandomNumberGenerator;
+
+pub struct RoomsAndCorridorsBuilder {
+    map : Map,
+    starting_position : Position,
+    artefact_position : Position
+}
+
+impl MapBuilder for RoomsAndCorridorsBuilder {
+    fn build_map(&mut self, rng : &mut RandomNumberGenerator) {
+        const MAX_ROOMS : i32 = 30;
+        const MIN_SIZE : i32 = 6;
+        const MAX_SIZE : i32 = 10;
+
+        for i in 0..MAX_ROOMS {
+            let room_width;
+            let room_height;
+            let x;
+            let y;
+            if i == 0{
+                room_width = rng.range(MIN_SIZE, MAX_SIZE);
+                room_height = rng.range(MIN_SIZE, MAX_SIZE);
+                x = 1;
+                y = 1;
+            }else{
+                room_width = rng.range(MIN_SIZE, MAX_SIZE);
+                room_height = rng.range(MIN_SIZE, MAX_SIZE);
+                x = rng.roll_dice(1, self.map.width - room_width - 1) -1;
+                y = rng.roll_dice(1, self.map.height - room_height - 1) -1;
+
+            }
+            let new_room = Rect::new(x, y, room_width, room_height);
+            let mut ok = true;
+            for other_room in self.map.rooms.iter() {
+                if new_room.intersect(other_room) { ok = false}
+            }
+            if ok {
+                apply_room_to_map(&mut self.map, &new_room);
+
+                if !self.map.rooms.is_empty() {
+                    let (new_x, new_y) = new_room.center();
+                    let (prev_x, prev_y) = self.map.rooms[self.map.rooms.len()-1].center();
+                    if rng.range(0,2) == 1 {
+                        apply_horizontal_tunnel(&mut self.map, prev_x, new_x, prev_y);
+                        apply_vertical_tunnel(&mut self.map, prev_y, new_y, new_x);
+                    } else {
+                        apply_vertical_tunnel(&mut self.map, prev_y, new_y, prev_x);
+                        apply_horizontal_tunnel(&mut self.map, prev_x, new_x, new_y);
+                    }
+                }
+
+                self.map.rooms.push(new_room);
+            }
+        }
+
+        add_doors(&mut self.map, rng, 50);
+
+        let (start_x, start_y) = self.map.rooms[0].center();
+        self.starting_position = Position{ x: start_x, y: start_y };
+        let (artefact_x, artefact_y) = self.map.rooms.last().unwrap().center();
+        self.artefact_position = Position{ x: artefact_x, y: artefact_y };
+    }
+
+    fn get_map(&self) -> Map {
+        self.map.clone()
+    }
+
+    fn get_starting_position(&self) -> Position {
+        self.starting_position.clone()
+    }
+
+    fn get_artefact_position(&self) -> Position {
+        self.artefact_position.clone()
+    }
+}
+
+impl RoomsAndCorridorsBuilder {
+    pub fn new(new_depth : i32) -> RoomsAndCorridorsBuilder {
+        RoomsAndCorridorsBuilder{
+            map : Map::new("The wreck", new_depth),
+            starting_position : Position{ x: 0, y: 0 },
+            artefact_position : Position{ x: 0, y: 0 }
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use super::{gamelog::GameLog, CombatStats, Name, SufferDamage, WantsToMelee};
-use crate::{Equipped, MeleePowerBonus};
+use crate::{DamageType, Equipped, Exertion, MakesNoise, MeleePowerBonus};
+use crate::noise_system::MELEE_NOISE;
+use crate::stamina_system::MELEE_STAMINA_COST;
+use crate::attack_roll::{self, AttackRoll};
+use rltk::RandomNumberGenerator;
 use specs::prelude::*;
 
 pub struct MeleeCombatSystem {}
@@ -13,18 +17,25 @@ impl<'a> System<'a> for MeleeCombatSystem {
                         ReadStorage<'a, CombatStats>,
                         WriteStorage<'a, SufferDamage>,
                         ReadStorage<'a, MeleePowerBonus>,
-                        ReadStorage<'a, Equipped>
+                        ReadStorage<'a, Equipped>,
+                        WriteStorage<'a, Exertion>,
+                        WriteExpect<'a, RandomNumberGenerator>,
+                        WriteStorage<'a, MakesNoise>
     );
 
     fn run(&mut self, data : Self::SystemData) {
-        let (entities, mut log, mut wants_melee, names, combat_stats, mut inflict_damage, meele_power_bonuses, equipped) = data;
+        let (entities, mut log, mut wants_melee, names, combat_stats, mut inflict_damage, meele_power_bonuses, equipped, mut exertions, mut rng, mut noises) = data;
 
         for (entity, wants_melee, name, stats) in (&entities, &wants_melee, &names, &combat_stats).join() {
             if stats.hp.current > 0 {
                 let mut offensive_bonus = 0;
+                let mut accuracy = 0;
+                let mut damage_type = DamageType::Kinetic;
                 for (_item_entity, melee_power_bonus, equipped_by) in (&entities, &meele_power_bonuses, &equipped).join() {
                     if equipped_by.owner == entity {
                         offensive_bonus += melee_power_bonus.power;
+                        accuracy += melee_power_bonus.accuracy;
+                        damage_type = melee_power_bonus.damage_type;
                     }
                 }
 
@@ -32,20 +43,36 @@ impl<'a> System<'a> for MeleeCombatSystem {
                 if target_stats.hp.current > 0 {
                     let target_name = names.get(wants_melee.target).unwrap();
 
-                    let damage = i32::max(0, (stats.power + offensive_bonus) - target_stats.defense );
+                    let mut power = stats.power + offensive_bonus;
+                    // Swinging at something takes stamina, an exhausted attacker only lands feeble blows
+                    if stats.stamina.max > 0 {
+                        if stats.stamina.current < MELEE_STAMINA_COST {
+                            power /= 2;
+                            log.entries.push(format!("{} is too exhausted to strike hard.", &name.name));
+                        }
+                        Exertion::new_exertion(&mut exertions, entity, MELEE_STAMINA_COST);
+                    }
+                    MakesNoise::new_noise(&mut noises, entity, MELEE_NOISE);
 
+                    let roll = attack_roll::roll_attack(&mut rng, accuracy, target_stats.evasion);
+                    let damage = attack_roll::apply_roll(roll, i32::max(0, power - target_stats.defense));
 
-                    if damage == 0 {
+                    if roll == AttackRoll::Miss {
+                        log.entries.push(format!(
+                            "{} misses {}.",
+                            &name.name, &target_name.name
+                        ));
+                    } else if damage == 0 {
                         log.entries.push(format!(
                             "{} is unable to hurt {}",
                             &name.name, &target_name.name
                         ));
                     } else {
                         log.entries.push(format!(
-                            "{} hits {}, for {} hp.",
-                            &name.name, &target_name.name, damage
+                            "{} {} {}, for {} {} damage.",
+                            &name.name, attack_roll::hit_verb(roll), &target_name.name, damage, damage_type.name()
                         ));
-                        SufferDamage::new_damage(&mut inflict_damage, wants_melee.target, damage);
+                        SufferDamage::new_damage(&mut inflict_damage, wants_melee.target, damage, damage_type);
                     }
                 } else {}
             }
@@ -0,0 +1,79 @@
+use specs::prelude::*;
+use super::{Map, TileType, Position};
+use crate::{AiState, Brain, MakesNoise};
+use crate::robot_ai_system::SEARCH_TURNS;
+use rltk::Point;
+use std::collections::BinaryHeap;
+
+pub const SHOT_NOISE : i32 = 16;
+pub const MELEE_NOISE : i32 = 8;
+pub const DOOR_NOISE : i32 = 6;
+pub const CRANK_NOISE : i32 = 10;
+// A closed door swallows this much of a sound going through it
+const DOOR_DAMPING : i32 = 6;
+
+pub struct NoiseSystem {}
+
+impl<'a> System<'a> for NoiseSystem {
+    type SystemData = ( Entities<'a>,
+                        ReadExpect<'a, Map>,
+                        WriteStorage<'a, MakesNoise>,
+                        ReadStorage<'a, Position>,
+                        WriteStorage<'a, Brain> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, map, mut noises, positions, mut brains) = data;
+
+        for (source, noise, pos) in (&entities, &noises, &positions).join() {
+            let loudness = noise.loudness.iter().max().copied().unwrap_or(0);
+            if loudness <= 0 { continue; }
+            let origin = Point::new(pos.x, pos.y);
+            let volume = spread(&map, origin, loudness);
+
+            // Robots that aren't busy with something else come to see what made the sound
+            for (listener, brain, listener_pos) in (&entities, &mut brains, &positions).join() {
+                if listener == source { continue; }
+                if brain.state == AiState::Chase || brain.state == AiState::Flee { continue; }
+                if volume[map.xy_idx(listener_pos.x, listener_pos.y)] > 0 {
+                    brain.state = AiState::Investigate;
+                    brain.last_seen = Some(origin);
+                    brain.search_turns = SEARCH_TURNS;
+                }
+            }
+        }
+
+        noises.clear();
+    }
+}
+
+// How loud a sound still is on every tile after travelling the shortest way there.
+// Walls stop it, closed doors muffle it and it doesn't carry through vacuum at all.
+pub fn spread(map : &Map, origin : Point, loudness : i32) -> Vec<i32> {
+    let mut volume = vec![0; map.tiles.len()];
+    let start = map.xy_idx(origin.x, origin.y);
+    volume[start] = loudness;
+
+    let mut open : BinaryHeap<(i32, usize)> = BinaryHeap::new();
+    open.push((loudness, start));
+    while let Some((level, idx)) = open.pop() {
+        if level < volume[idx] { continue; }
+        let x = idx as i32 % map.width;
+        let y = idx as i32 / map.width;
+        for (nx, ny) in [(x-1, y), (x+1, y), (x, y-1), (x, y+1)].iter() {
+            if *nx < 0 || *nx >= map.width || *ny < 0 || *ny >= map.height { continue; }
+            let neighbour = map.xy_idx(*nx, *ny);
+            let cost = match map.tiles[neighbour] {
+                TileType::DoorClosed => DOOR_DAMPING,
+                tile if tile.is_solid() => continue,
+                _ if map.atmosphere[neighbour] <= 0 => continue,
+                _ => 1
+            };
+            let remaining = level - cost;
+            if remaining > volume[neighbour] {
+                volume[neighbour] = remaining;
+                open.push((remaining, neighbour));
+            }
+        }
+    }
+    volume
+}
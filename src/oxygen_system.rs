@@ -1,5 +1,7 @@
-use super::{gamelog::GameLog, CombatStats, Name, Player, RunState, SufferDamage};
-use crate::BreathOxygen;
+use super::{gamelog::GameLog, CombatStats, Name, Player, RunState, SufferDamage, Map, Position};
+use crate::{BreathOxygen, DamageType, Equipped, OxygenEfficiency};
+use rltk::RandomNumberGenerator;
+use crate::atmosphere_system::BREATHABLE_PRESSURE;
 use specs::prelude::*;
 use std::cmp::max;
 
@@ -12,16 +14,39 @@ impl<'a> System<'a> for OxygenSystem {
         WriteStorage<'a, CombatStats>,
         WriteStorage<'a, BreathOxygen>,
         WriteStorage<'a, SufferDamage>,
+        ReadExpect<'a, Map>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, OxygenEfficiency>,
+        WriteExpect<'a, RandomNumberGenerator>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut log, mut stats, mut breaths, mut damages) = data;
+        let (entities, mut log, mut stats, mut breaths, mut damages, map, positions, equipped, efficiencies, mut rng) = data;
 
         for (entity, mut stats, breath) in (&entities, &mut stats, &breaths).join() {
+            // Pressurized sections are breathable, the suit only draws on its own supply in vacuum
+            if let Some(pos) = positions.get(entity) {
+                if map.atmosphere[map.xy_idx(pos.x, pos.y)] >= BREATHABLE_PRESSURE {
+                    continue;
+                }
+            }
+
+            // Rebreather modules sometimes recycle a whole breath
+            let mut efficiency = 0;
+            for (equipped_by, saver) in (&equipped, &efficiencies).join() {
+                if equipped_by.owner == entity {
+                    efficiency += saver.percent;
+                }
+            }
+            if efficiency > 0 && rng.roll_dice(1, 100) <= efficiency {
+                continue;
+            }
+
             stats.oxygen.current = max(0, stats.oxygen.current - breath.amount.iter().sum::<i32>());
             match stats.oxygen.current {
                 0 => {
-                    SufferDamage::new_damage(&mut damages, entity, 1);
+                    SufferDamage::new_damage(&mut damages, entity, 1, DamageType::Kinetic);
                     log.entries.push(format!("You suffer damage because you are out of air."))
                 },
                 25 => log.entries.push(format!("Oxygen level dropped to 25%")),
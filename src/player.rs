@@ -1,31 +1,82 @@
 use rltk::{Point};
-use rltk::{VirtualKeyCode, Rltk};
+use rltk::{VirtualKeyCode, Rltk, RandomNumberGenerator};
 use specs::prelude::*;
-use super::{Position, Player, Map, State, Viewshed, RunState, CombatStats, WantsToMelee, Item, gamelog::GameLog, WantsToPickupItem};
+use super::{Position, Player, Map, State, Viewshed, RunState, CombatStats, WantsToMelee, Item, gamelog::GameLog, WantsToPickupItem, TileType};
 use std::cmp::{min, max};
-use crate::{Equipped, RangedWeapon, Robot, Target, WantsToShoot, Name, BreathOxygen, ArtefactFromYendoria};
-
-pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
+use crate::{Equipped, RangedWeapon, Faction, Target, WantsToShoot, Name, ArtefactFromYendoria, Exertion, WantsToRest,
+            Magazine, AmmoType, AmmoPack, ProvidesEnergy, PowerCore, InBackpack, Aiming, FiringPattern, Wreckage, MakesNoise, TimeCost, Companion, Immobile};
+use crate::spawner;
+use crate::stamina_system::SPRINT_STAMINA_COST;
+use crate::noise_system::{DOOR_NOISE, CRANK_NOISE};
+use crate::faction;
+use crate::initiative_system::{SPRINT_TIME, AIM_TIME, RELOAD_TIME};
+
+const SPRINT_DISTANCE : i32 = 3;
+
+pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
+    // Moving spoils any aim taken through a scope
+    ecs.write_storage::<Aiming>().clear();
     let mut positions = ecs.write_storage::<Position>();
     let mut players = ecs.write_storage::<Player>();
     let mut viewshed = ecs.write_storage::<Viewshed>();
     let combat_stats = ecs.read_storage::<CombatStats>();
+    let factions = ecs.read_storage::<Faction>();
+    let companions = ecs.read_storage::<Companion>();
+    let immobile = ecs.read_storage::<Immobile>();
     let entities = ecs.entities();
     let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
-    let map = ecs.fetch::<Map>();
+    let mut map = ecs.fetch_mut::<Map>();
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let mut door_opened = false;
+    let mut door_cranked = false;
+    let mut swap : Option<(Entity, i32, i32)> = None;
+    let mut moved_from : Option<(i32, i32)> = None;
 
     for (entity, _player, pos, _viewshed) in (&entities, &mut players, &mut positions, &mut viewshed).join() {
-        if pos.x + delta_x < 1 || pos.x + delta_x > map.width-1 || pos.y + delta_y < 1 || pos.y + delta_y > map.height-1 { return; }
+        if pos.x + delta_x < 1 || pos.x + delta_x > map.width-1 || pos.y + delta_y < 1 || pos.y + delta_y > map.height-1 { return RunState::PlayerTurn; }
         let destination_idx = map.xy_idx(pos.x + delta_x, pos.y + delta_y);
 
+        let mut companion : Option<Entity> = None;
         for potential_target in map.tile_content[destination_idx].iter() {
             let target = combat_stats.get(*potential_target);
             if let Some(_target) = target {
+                // Bolted-down machines like turrets can't step aside, their tile stays blocked
+                if companions.get(*potential_target).is_some() && immobile.get(*potential_target).is_none() {
+                    companion = Some(*potential_target);
+                }
+                if !faction::is_hostile(factions.get(entity), factions.get(*potential_target)) { continue; }
                 wants_to_melee.insert(entity, WantsToMelee{ target: *potential_target }).expect("Add target failed");
-                return;
+                return RunState::PlayerTurn;
             }
         }
-        if !map.blocked_tiles[destination_idx] {
+        // Companions step aside by trading places
+        if let Some(companion) = companion {
+            swap = Some((companion, pos.x, pos.y));
+            moved_from = Some((pos.x, pos.y));
+            pos.x += delta_x;
+            pos.y += delta_y;
+
+            let mut ppos = ecs.write_resource::<Point>();
+            ppos.x = pos.x;
+            ppos.y = pos.y;
+
+            _viewshed.dirty = true;
+            continue;
+        }
+        if map.tiles[destination_idx] == TileType::Console {
+            return RunState::ShowPowerConsole;
+        }
+        // Bumping into a closed door opens it, without power it has to be cranked open by hand
+        if map.tiles[destination_idx] == TileType::DoorClosed {
+            if map.power.doors || rng.roll_dice(1, 3) == 1 {
+                map.tiles[destination_idx] = TileType::DoorOpen;
+                map.blocked_tiles[destination_idx] = false;
+                door_opened = true;
+            } else {
+                door_cranked = true;
+            }
+        } else if !map.blocked_tiles[destination_idx] {
+            moved_from = Some((pos.x, pos.y));
             pos.x = min(map.width-1 , max(0, pos.x + delta_x));
             pos.y = min(map.height-1, max(0, pos.y + delta_y));
 
@@ -36,28 +87,175 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
             _viewshed.dirty = true;
         }
     }
+
+    let player_entity = *ecs.fetch::<Entity>();
+    if let Some((companion, x, y)) = swap {
+        if let Some(companion_pos) = positions.get_mut(companion) {
+            companion_pos.x = x;
+            companion_pos.y = y;
+        }
+
+        // Whatever the companion was carrying is handed over on the way past
+        let mut backpack = ecs.write_storage::<InBackpack>();
+        let names = ecs.read_storage::<Name>();
+        let mut handed_over : Vec<String> = Vec::new();
+        for (item, carried) in (&entities, &mut backpack).join() {
+            if carried.owner == companion {
+                carried.owner = player_entity;
+                handed_over.push(names.get(item).map_or("something".to_string(), |name| name.name.clone()));
+            }
+        }
+        if !handed_over.is_empty() {
+            ecs.fetch_mut::<GameLog>().entries.push(format!("Your companion hands over: {}.", handed_over.join(", ")));
+        }
+    }
+    if door_opened {
+        // Everyone near the door may see through it now
+        for viewshed in (&mut viewshed).join() {
+            viewshed.dirty = true;
+        }
+        ecs.fetch_mut::<GameLog>().entries.push("You open the door.".to_string());
+        MakesNoise::new_noise(&mut ecs.write_storage::<MakesNoise>(), player_entity, DOOR_NOISE);
+    }
+    if door_cranked {
+        ecs.fetch_mut::<GameLog>().entries.push("You crank at the unpowered door.".to_string());
+        MakesNoise::new_noise(&mut ecs.write_storage::<MakesNoise>(), player_entity, CRANK_NOISE);
+    }
+    // Walking into another room tells the salvager what it used to be
+    if let Some((from_x, from_y)) = moved_from {
+        let player_pos = *ecs.fetch::<Point>();
+        let room = map.room_at(player_pos.x, player_pos.y);
+        if room.is_some() && room != map.room_at(from_x, from_y) {
+            if let Some(room_type) = map.room_type_at(player_pos.x, player_pos.y) {
+                ecs.fetch_mut::<GameLog>().entries.push(format!("You enter the {}.", room_type.name().to_lowercase()));
+            }
+        }
+    }
+    RunState::PlayerTurn
 }
 
-fn get_item(ecs: &mut World) {
-    let player_pos = ecs.fetch::<Point>();
+// Dash several tiles in one turn, stopping short of anything in the way
+fn try_sprint_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
+    ecs.write_storage::<Aiming>().clear();
+    let player_entity = *ecs.fetch::<Entity>();
+    let winded = ecs.read_storage::<CombatStats>().get(player_entity).map_or(true, |stats| stats.stamina.current < SPRINT_STAMINA_COST);
+    if winded {
+        ecs.fetch_mut::<GameLog>().entries.push("You are too winded to sprint.".to_string());
+        return RunState::AwaitingInput;
+    }
+
+    let mut steps = 0;
+    {
+        let map = ecs.fetch::<Map>();
+        let mut positions = ecs.write_storage::<Position>();
+        if let Some(pos) = positions.get_mut(player_entity) {
+            while steps < SPRINT_DISTANCE {
+                let x = pos.x + delta_x;
+                let y = pos.y + delta_y;
+                if x < 1 || x > map.width-1 || y < 1 || y > map.height-1 { break; }
+                if map.blocked_tiles[map.xy_idx(x, y)] { break; }
+                pos.x = x;
+                pos.y = y;
+                steps += 1;
+            }
+            let mut ppos = ecs.write_resource::<Point>();
+            ppos.x = pos.x;
+            ppos.y = pos.y;
+        }
+    }
+
+    // Nowhere to run, treat it as an ordinary bump so doors and robots still react
+    if steps == 0 {
+        return try_move_player(delta_x, delta_y, ecs);
+    }
+
+    if let Some(viewshed) = ecs.write_storage::<Viewshed>().get_mut(player_entity) {
+        viewshed.dirty = true;
+    }
+    let mut exertions = ecs.write_storage::<Exertion>();
+    Exertion::new_exertion(&mut exertions, player_entity, SPRINT_STAMINA_COST);
+    ecs.write_storage::<TimeCost>().insert(player_entity, TimeCost{ amount: SPRINT_TIME }).expect("Unable to insert time cost");
+    RunState::PlayerTurn
+}
+
+fn rest(ecs: &mut World) {
     let player_entity = ecs.fetch::<Entity>();
-    let entities = ecs.entities();
-    let items = ecs.read_storage::<Item>();
-    let positions = ecs.read_storage::<Position>();
+    ecs.write_storage::<WantsToRest>().insert(*player_entity, WantsToRest{}).expect("Unable to insert rest");
+}
+
+fn close_door(ecs: &mut World) -> bool {
+    let player_pos = ecs.fetch::<Point>();
+    let mut map = ecs.fetch_mut::<Map>();
+    let mut viewsheds = ecs.write_storage::<Viewshed>();
     let mut gamelog = ecs.fetch_mut::<GameLog>();
 
-    let mut target_item : Option<Entity> = None;
-    for (item_entity, _item, position) in (&entities, &items, &positions).join() {
-        if position.x == player_pos.x && position.y == player_pos.y {
-            target_item = Some(item_entity);
+    for delta_y in -1 ..= 1 {
+        for delta_x in -1 ..= 1 {
+            let x = player_pos.x + delta_x;
+            let y = player_pos.y + delta_y;
+            if x < 1 || x > map.width-1 || y < 1 || y > map.height-1 { continue; }
+            let idx = map.xy_idx(x, y);
+            // Something standing or lying in the doorway keeps it open
+            if map.tiles[idx] == TileType::DoorOpen && map.tile_content[idx].is_empty() {
+                map.tiles[idx] = TileType::DoorClosed;
+                map.blocked_tiles[idx] = true;
+                for viewshed in (&mut viewsheds).join() {
+                    viewshed.dirty = true;
+                }
+                gamelog.entries.push("You close the door.".to_string());
+                return true;
+            }
+        }
+    }
+
+    gamelog.entries.push("There is no open door next to you.".to_string());
+    false
+}
+
+fn get_item(ecs: &mut World) {
+    let mut salvage : Option<Entity> = None;
+    // Scope to keep the borrow checker happy
+    {
+        let player_pos = ecs.fetch::<Point>();
+        let player_entity = ecs.fetch::<Entity>();
+        let entities = ecs.entities();
+        let items = ecs.read_storage::<Item>();
+        let wrecks = ecs.read_storage::<Wreckage>();
+        let positions = ecs.read_storage::<Position>();
+        let mut gamelog = ecs.fetch_mut::<GameLog>();
+
+        let mut target_item : Option<Entity> = None;
+        for (item_entity, _item, position) in (&entities, &items, &positions).join() {
+            if position.x == player_pos.x && position.y == player_pos.y {
+                target_item = Some(item_entity);
+            }
+        }
+
+        let mut target_wreck : Option<Entity> = None;
+        for (wreck_entity, _wreck, position) in (&entities, &wrecks, &positions).join() {
+            if position.x == player_pos.x && position.y == player_pos.y {
+                target_wreck = Some(wreck_entity);
+            }
+        }
+
+        match (target_item, target_wreck) {
+            (Some(item), _) => {
+                let mut pickup = ecs.write_storage::<WantsToPickupItem>();
+                pickup.insert(*player_entity, WantsToPickupItem{ collected_by: *player_entity, item }).expect("Unable to insert want to pickup");
+            }
+            (None, Some(wreck)) => salvage = Some(wreck),
+            (None, None) => gamelog.entries.push("There is nothing here to pick up.".to_string())
         }
     }
 
-    match target_item {
-        None => gamelog.entries.push("There is nothing here to pick up.".to_string()),
-        Some(item) => {
-            let mut pickup = ecs.write_storage::<WantsToPickupItem>();
-            pickup.insert(*player_entity, WantsToPickupItem{ collected_by: *player_entity, item }).expect("Unable to insert want to pickup");
+    // Stripping a wreck spawns its parts on the floor, ready to be picked up
+    if let Some(wreck) = salvage {
+        let drops = spawner::salvage_wreck(ecs, wreck);
+        let mut gamelog = ecs.fetch_mut::<GameLog>();
+        if drops.is_empty() {
+            gamelog.entries.push("You strip the wreck, but nothing in it is worth keeping.".to_string());
+        } else {
+            gamelog.entries.push(format!("You strip the wreck and salvage: {}.", drops.join(", ")));
         }
     }
 }
@@ -81,6 +279,22 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState{
         if let Some(key) = key {
             return use_consumable_hotkey(gs, key-1);
         }
+
+        let sprint : Option<(i32, i32)> =
+            match ctx.key.unwrap() {
+                VirtualKeyCode::Left | VirtualKeyCode::Numpad4 | VirtualKeyCode::H => Some((-1, 0)),
+                VirtualKeyCode::Right | VirtualKeyCode::Numpad6 | VirtualKeyCode::L => Some((1, 0)),
+                VirtualKeyCode::Up | VirtualKeyCode::Numpad8 | VirtualKeyCode::K => Some((0, -1)),
+                VirtualKeyCode::Down | VirtualKeyCode::Numpad2 | VirtualKeyCode::J => Some((0, 1)),
+                VirtualKeyCode::Numpad9 | VirtualKeyCode::Y => Some((1, -1)),
+                VirtualKeyCode::Numpad7 | VirtualKeyCode::U => Some((-1, -1)),
+                VirtualKeyCode::Numpad3 | VirtualKeyCode::N => Some((1, 1)),
+                VirtualKeyCode::Numpad1 | VirtualKeyCode::B => Some((-1, 1)),
+                _ => None
+            };
+        if let Some((delta_x, delta_y)) = sprint {
+            return try_sprint_player(delta_x, delta_y, &mut gs.ecs);
+        }
     }
     // Player movement
     match ctx.key {
@@ -88,32 +302,36 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState{
         Some(key) => match key {
             VirtualKeyCode::Left |
             VirtualKeyCode::Numpad4 |
-            VirtualKeyCode::H => try_move_player(-1, 0, &mut gs.ecs),
+            VirtualKeyCode::H => return try_move_player(-1, 0, &mut gs.ecs),
 
             VirtualKeyCode::Right |
             VirtualKeyCode::Numpad6 |
-            VirtualKeyCode::L => try_move_player(1, 0, &mut gs.ecs),
+            VirtualKeyCode::L => return try_move_player(1, 0, &mut gs.ecs),
 
             VirtualKeyCode::Up |
             VirtualKeyCode::Numpad8 |
-            VirtualKeyCode::K => try_move_player(0, -1, &mut gs.ecs),
+            VirtualKeyCode::K => return try_move_player(0, -1, &mut gs.ecs),
 
             VirtualKeyCode::Down |
             VirtualKeyCode::Numpad2 |
-            VirtualKeyCode::J => try_move_player(0, 1, &mut gs.ecs),
+            VirtualKeyCode::J => return try_move_player(0, 1, &mut gs.ecs),
 
             // Diagonals
             VirtualKeyCode::Numpad9 |
-            VirtualKeyCode::Y => try_move_player(1, -1, &mut gs.ecs),
+            VirtualKeyCode::Y => return try_move_player(1, -1, &mut gs.ecs),
 
             VirtualKeyCode::Numpad7 |
-            VirtualKeyCode::U => try_move_player(-1, -1, &mut gs.ecs),
+            VirtualKeyCode::U => return try_move_player(-1, -1, &mut gs.ecs),
 
             VirtualKeyCode::Numpad3 |
-            VirtualKeyCode::N => try_move_player(1, 1, &mut gs.ecs),
+            VirtualKeyCode::N => return try_move_player(1, 1, &mut gs.ecs),
 
             VirtualKeyCode::Numpad1 |
-            VirtualKeyCode::B => try_move_player(-1, 1, &mut gs.ecs),
+            VirtualKeyCode::B => return try_move_player(-1, 1, &mut gs.ecs),
+
+            // Catch your breath
+            VirtualKeyCode::Space |
+            VirtualKeyCode::Numpad5 => rest(&mut gs.ecs),
 
             VirtualKeyCode::G => {
                 get_item(&mut gs.ecs);
@@ -121,6 +339,24 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState{
                     return RunState::GameWon;
                 }
             },
+            // Lifts
+            VirtualKeyCode::Period => {
+                if try_next_deck(&mut gs.ecs) {
+                    return RunState::NextDeck;
+                }
+            }
+            VirtualKeyCode::Comma => {
+                if try_previous_deck(&mut gs.ecs) {
+                    return RunState::PreviousDeck;
+                }
+            }
+
+            VirtualKeyCode::C => {
+                if !close_door(&mut gs.ecs) {
+                    return RunState::AwaitingInput;
+                }
+            }
+
             VirtualKeyCode::I => return RunState::ShowInventory,
             VirtualKeyCode::D => return RunState::ShowDropItem,
             VirtualKeyCode::Escape => return RunState::SaveGame,
@@ -131,6 +367,11 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState{
                 return RunState::AwaitingInput;
             }
             VirtualKeyCode::F => fire_on_target(&mut gs.ecs),
+            VirtualKeyCode::X => {
+                if !reload_weapon(&mut gs.ecs) {
+                    return RunState::AwaitingInput;
+                }
+            }
 
             _ => { return RunState::AwaitingInput}
         },
@@ -138,6 +379,32 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState{
     RunState::PlayerTurn
 }
 
+pub fn try_next_deck(ecs: &mut World) -> bool {
+    let player_pos = ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let player_idx = map.xy_idx(player_pos.x, player_pos.y);
+    if map.tiles[player_idx] == TileType::LiftDown {
+        true
+    } else {
+        let mut gamelog = ecs.fetch_mut::<GameLog>();
+        gamelog.entries.push("There is no lift down from here.".to_string());
+        false
+    }
+}
+
+pub fn try_previous_deck(ecs: &mut World) -> bool {
+    let player_pos = ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let player_idx = map.xy_idx(player_pos.x, player_pos.y);
+    if map.tiles[player_idx] == TileType::LiftUp {
+        true
+    } else {
+        let mut gamelog = ecs.fetch_mut::<GameLog>();
+        gamelog.entries.push("There is no lift up from here.".to_string());
+        false
+    }
+}
+
 fn check_game_won(ecs: &mut World) -> bool {
     let player_entity = ecs.fetch::<Entity>();
     let entities = ecs.entities();
@@ -190,7 +457,7 @@ fn get_player_target_list(ecs : &mut World) -> Vec<(f32,Entity)> {
     let ranged_weapons = ecs.read_storage::<RangedWeapon>();
     let map = ecs.fetch::<Map>();
     let positions = ecs.read_storage::<Position>();
-    let robots = ecs.read_storage::<Robot>();
+    let factions = ecs.read_storage::<Faction>();
     for (equipped, ranged_weapon) in (&equipped, &ranged_weapons).join() {
         if equipped.owner == *player_entity {
             let range = ranged_weapon.range;
@@ -202,7 +469,7 @@ fn get_player_target_list(ecs : &mut World) -> Vec<(f32,Entity)> {
                     let distance_to_target = rltk::DistanceAlg::Pythagoras.distance2d(*tile_point, rltk::Point::new(player_pos.x, player_pos.y));
                     if distance_to_target < range as f32 {
                         for possible_target in map.tile_content[tile_idx].iter() {
-                            if *possible_target != *player_entity && robots.get(*possible_target).is_some() {
+                            if *possible_target != *player_entity && faction::is_hostile(factions.get(*player_entity), factions.get(*possible_target)) {
                                 possible_targets.push((distance_to_target, *possible_target));
                             }
                         }
@@ -292,6 +559,25 @@ fn fire_on_target(ecs: &mut World) {
         let player_entity = ecs.fetch::<Entity>();
         let mut shoot_store = ecs.write_storage::<WantsToShoot>();
         let names = ecs.read_storage::<Name>();
+
+        // A scoped weapon spends the first turn lining up the shot
+        let mut scoped = false;
+        for (equipped_by, weapon) in (&ecs.read_storage::<Equipped>(), &ecs.read_storage::<RangedWeapon>()).join() {
+            if equipped_by.owner == *player_entity && weapon.pattern == FiringPattern::Scoped {
+                scoped = true;
+            }
+        }
+        let mut aiming = ecs.write_storage::<Aiming>();
+        let aimed = aiming.get(*player_entity).map_or(false, |aim| aim.target == target);
+        if scoped && !aimed {
+            if let Some(name) = names.get(target) {
+                log.entries.push(format!("You take aim at {}.", name.name));
+            }
+            aiming.insert(*player_entity, Aiming{ target }).expect("Insert Fail");
+            ecs.write_storage::<TimeCost>().insert(*player_entity, TimeCost{ amount: AIM_TIME }).expect("Insert Fail");
+            return;
+        }
+
         if let Some(name) = names.get(target) {
             log.entries.push(format!("You fire at {}", name.name));
         }
@@ -302,8 +588,67 @@ fn fire_on_target(ecs: &mut World) {
 
 }
 
-pub fn end_turn_breathing(ecs: &mut World){
-    let player_entity = ecs.fetch::<Entity>();
-    let mut oxygen_store = ecs.write_storage::<BreathOxygen>();
-    BreathOxygen::new_breath(&mut oxygen_store, *player_entity, 1)
+// Swaps a fresh battery or ammo pack from the backpack into the equipped weapon
+fn reload_weapon(ecs: &mut World) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let equipped = ecs.read_storage::<Equipped>();
+    let mut magazines = ecs.write_storage::<Magazine>();
+    let backpack = ecs.read_storage::<InBackpack>();
+    let batteries = ecs.read_storage::<ProvidesEnergy>();
+    let cores = ecs.read_storage::<PowerCore>();
+    let ammo_packs = ecs.read_storage::<AmmoPack>();
+    let names = ecs.read_storage::<Name>();
+    let mut log = ecs.fetch_mut::<GameLog>();
+
+    let mut weapon : Option<Entity> = None;
+    for (entity, equipped_by, _magazine) in (&entities, &equipped, &magazines).join() {
+        if equipped_by.owner == player_entity {
+            weapon = Some(entity);
+        }
+    }
+    let weapon = match weapon {
+        Some(weapon) => weapon,
+        None => {
+            log.entries.push("You have nothing to reload.".to_string());
+            return false;
+        }
+    };
+
+    let magazine = magazines.get_mut(weapon).unwrap();
+    if magazine.current >= magazine.capacity {
+        log.entries.push("Your weapon is already fully loaded.".to_string());
+        return false;
+    }
+
+    let mut refill : Option<Entity> = None;
+    for (entity, pack) in (&entities, &backpack).join() {
+        if pack.owner != player_entity { continue; }
+        let fits = match magazine.ammo {
+            AmmoType::EnergyCell => batteries.get(entity).is_some() && cores.get(entity).is_none(),
+            AmmoType::Slugs => ammo_packs.get(entity).is_some()
+        };
+        if fits {
+            refill = Some(entity);
+        }
+    }
+
+    match refill {
+        None => {
+            match magazine.ammo {
+                AmmoType::EnergyCell => log.entries.push("You have no battery to recharge your weapon.".to_string()),
+                AmmoType::Slugs => log.entries.push("You have no ammo pack for your weapon.".to_string())
+            }
+            false
+        }
+        Some(refill) => {
+            magazine.current = magazine.capacity;
+            if let Some(name) = names.get(refill) {
+                log.entries.push(format!("You reload with a {}.", name.name));
+            }
+            entities.delete(refill).expect("Unable to delete");
+            ecs.write_storage::<TimeCost>().insert(player_entity, TimeCost{ amount: RELOAD_TIME }).expect("Unable to insert time cost");
+            true
+        }
+    }
 }
\ No newline at end of file
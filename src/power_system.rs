@@ -0,0 +1,133 @@
+use specs::prelude::*;
+use serde::{Serialize, Deserialize};
+use super::{Map, TileType, Position, PowerCore, Viewshed, gamelog::GameLog};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum Subsystem { LifeSupport, Heating, Lighting, Doors, Sensors, Fabrication }
+
+// Which subsystems of a deck the salvager routed power to, and what the deck's cores can deliver
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct PowerGrid {
+    pub capacity : i32,
+    pub life_support : bool,
+    pub heating : bool,
+    pub lighting : bool,
+    pub doors : bool,
+    pub sensors : bool,
+    pub fabrication : bool
+}
+
+impl PowerGrid {
+    pub fn demand(&self) -> i32 {
+        [self.life_support, self.heating, self.lighting, self.doors, self.sensors, self.fabrication].iter().filter(|on| **on).count() as i32
+    }
+
+    pub fn is_on(&self, subsystem : Subsystem) -> bool {
+        match subsystem {
+            Subsystem::LifeSupport => self.life_support,
+            Subsystem::Heating => self.heating,
+            Subsystem::Lighting => self.lighting,
+            Subsystem::Doors => self.doors,
+            Subsystem::Sensors => self.sensors,
+            Subsystem::Fabrication => self.fabrication
+        }
+    }
+
+    fn set(&mut self, subsystem : Subsystem, on : bool) {
+        match subsystem {
+            Subsystem::LifeSupport => self.life_support = on,
+            Subsystem::Heating => self.heating = on,
+            Subsystem::Lighting => self.lighting = on,
+            Subsystem::Doors => self.doors = on,
+            Subsystem::Sensors => self.sensors = on,
+            Subsystem::Fabrication => self.fabrication = on
+        }
+    }
+}
+
+pub fn subsystem_name(subsystem : Subsystem) -> &'static str {
+    match subsystem {
+        Subsystem::LifeSupport => "Life support",
+        Subsystem::Heating => "Heating",
+        Subsystem::Lighting => "Lighting",
+        Subsystem::Doors => "Doors",
+        Subsystem::Sensors => "Internal sensors",
+        Subsystem::Fabrication => "Fabricators"
+    }
+}
+
+pub struct PowerSystem {}
+
+impl<'a> System<'a> for PowerSystem {
+    type SystemData = ( WriteExpect<'a, Map>,
+                        WriteExpect<'a, GameLog>,
+                        ReadStorage<'a, PowerCore>,
+                        ReadStorage<'a, Position>,
+                        WriteStorage<'a, Viewshed> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (mut map, mut log, cores, positions, mut viewsheds) = data;
+
+        // Only cores still installed on this deck feed the grid
+        let mut capacity = 0;
+        for (core, _pos) in (&cores, &positions).join() {
+            capacity += core.output;
+        }
+        map.power.capacity = capacity;
+
+        // Shed load until the grid can carry it again, least important subsystems first
+        for subsystem in [Subsystem::Fabrication, Subsystem::Sensors, Subsystem::Doors, Subsystem::Lighting, Subsystem::Heating, Subsystem::LifeSupport].iter() {
+            if map.power.demand() > map.power.capacity && map.power.is_on(*subsystem) {
+                map.power.set(*subsystem, false);
+                log.entries.push(format!("The grid is overloaded, {} shuts down.", subsystem_name(*subsystem)));
+                if *subsystem == Subsystem::Lighting {
+                    for viewshed in (&mut viewsheds).join() {
+                        viewshed.dirty = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Builds the deck's power console into the nearest wall the salvager can reach from the docking spot
+pub fn install_console(map : &mut Map, start_x : i32, start_y : i32) {
+    for radius in 1..12 {
+        for y in start_y - radius ..= start_y + radius {
+            for x in start_x - radius ..= start_x + radius {
+                if x < 1 || x > map.width-2 || y < 1 || y > map.height-2 { continue; }
+                let idx = map.xy_idx(x, y);
+                if map.tiles[idx] != TileType::Wall { continue; }
+                let reachable = [(x-1, y), (x+1, y), (x, y-1), (x, y+1)].iter()
+                    .any(|(nx, ny)| map.tiles[map.xy_idx(*nx, *ny)] == TileType::Floor);
+                if reachable {
+                    map.tiles[idx] = TileType::Console;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+pub fn toggle_subsystem(ecs : &mut World, subsystem : Subsystem) {
+    let mut map = ecs.fetch_mut::<Map>();
+    let mut log = ecs.fetch_mut::<GameLog>();
+
+    if map.power.is_on(subsystem) {
+        map.power.set(subsystem, false);
+        log.entries.push(format!("You cut the power to {}.", subsystem_name(subsystem)));
+    } else if map.power.demand() < map.power.capacity {
+        map.power.set(subsystem, true);
+        log.entries.push(format!("You route power to {}.", subsystem_name(subsystem)));
+    } else {
+        log.entries.push(format!("There is not enough power left for {}.", subsystem_name(subsystem)));
+        return;
+    }
+
+    if subsystem == Subsystem::Lighting {
+        let mut viewsheds = ecs.write_storage::<Viewshed>();
+        for viewshed in (&mut viewsheds).join() {
+            viewshed.dirty = true;
+        }
+    }
+}
@@ -1,7 +1,14 @@
-use super::{gamelog::GameLog, CombatStats, Name, SufferDamage};
-use crate::{DrainEnergy, Equipped, RangedWeapon, WantsToShoot};
+use super::{gamelog::GameLog, CombatStats, Name, SufferDamage, Map, Position, BlocksTile};
+use crate::{Aiming, DamageType, DrainEnergy, Equipped, FiringPattern, MakesNoise, Magazine, RangedWeapon, WantsToShoot};
+use crate::noise_system::SHOT_NOISE;
+use crate::line_of_fire::{self, LineOfFire};
+use crate::attack_roll::{self, AttackRoll};
+use rltk::{Point, RandomNumberGenerator};
 use specs::prelude::*;
 
+// Accuracy gained by lining up a scoped shot for a turn
+pub const AIM_BONUS : i32 = 30;
+
 pub struct RangedCombatSystem {}
 
 impl<'a> System<'a> for RangedCombatSystem {
@@ -16,6 +23,14 @@ impl<'a> System<'a> for RangedCombatSystem {
         ReadStorage<'a, RangedWeapon>,
         ReadStorage<'a, Equipped>,
         WriteStorage<'a, DrainEnergy>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Map>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, BlocksTile>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteStorage<'a, Magazine>,
+        WriteStorage<'a, Aiming>,
+        WriteStorage<'a, MakesNoise>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -29,48 +44,145 @@ impl<'a> System<'a> for RangedCombatSystem {
             ranged_weapons,
             equipped,
             mut drain_energy,
+            player_entity,
+            map,
+            positions,
+            blockers,
+            mut rng,
+            mut magazines,
+            mut aiming,
+            mut noises,
         ) = data;
 
-        for (entity, wants_to_shoot, name, stats) in
-            (&entities, &wants_to_shoot, &names, &combat_stats).join()
+        for (entity, wants_to_shoot, name, stats, pos) in
+            (&entities, &wants_to_shoot, &names, &combat_stats, &positions).join()
         {
-            if stats.hp.current > 0 && stats.energy.current > 0{
-                let mut range_power = 0;
-                for (_item_entity, ranged_weapon, equipped_by) in
+            if stats.hp.current > 0 {
+                // Robots have their guns built in, everyone else shoots with what they have equipped
+                let mut weapon_entity = ranged_weapons.get(entity).map(|_| entity);
+                let mut range_power = ranged_weapons.get(entity).map_or(0, |weapon| weapon.damage);
+                let mut accuracy = ranged_weapons.get(entity).map_or(0, |weapon| weapon.accuracy);
+                let mut damage_type = ranged_weapons.get(entity).map_or(DamageType::Energy, |weapon| weapon.damage_type);
+                let mut pattern = ranged_weapons.get(entity).map_or(FiringPattern::Single, |weapon| weapon.pattern);
+                let mut range = ranged_weapons.get(entity).map_or(0, |weapon| weapon.range);
+                for (item_entity, ranged_weapon, equipped_by) in
                     (&entities, &ranged_weapons, &equipped).join()
                 {
                     if equipped_by.owner == entity {
+                        weapon_entity = Some(item_entity);
                         range_power = ranged_weapon.damage;
+                        accuracy = ranged_weapon.accuracy;
+                        damage_type = ranged_weapon.damage_type;
+                        pattern = ranged_weapon.pattern;
+                        range = ranged_weapon.range;
                     }
                 }
 
-                let target_stats = combat_stats.get(wants_to_shoot.target).unwrap();
-                if target_stats.hp.current > 0 {
-                    let target_name = names.get(wants_to_shoot.target).unwrap();
-
-                    let damage = i32::max(0, range_power - target_stats.defense);
+                let target_pos = match positions.get(wants_to_shoot.target) {
+                    Some(target_pos) => Point::new(target_pos.x, target_pos.y),
+                    None => continue
+                };
 
-                    if damage == 0 {
-                        log.entries.push(format!(
-                            "{} is unable to hurt {}",
-                            &name.name, &target_name.name
-                        ));
-                    } else {
-                        log.entries.push(format!(
-                            "{} hits {}, for {} hp.",
-                            &name.name, &target_name.name, damage
-                        ));
-                        SufferDamage::new_damage(
-                            &mut inflict_damage,
-                            wants_to_shoot.target,
-                            damage,
-                        );
+                // Built-in guns run off the robot's own energy, carried weapons off their magazine
+                match weapon_entity {
+                    None => continue,
+                    Some(weapon_entity) if weapon_entity == entity => {
+                        if stats.energy.current <= 0 { continue; }
                         DrainEnergy::new_energy(&mut drain_energy, entity, 1);
                     }
-                } else {
+                    Some(weapon_entity) => {
+                        if let Some(magazine) = magazines.get_mut(weapon_entity) {
+                            if magazine.current <= 0 {
+                                if entity == *player_entity {
+                                    log.entries.push("Your weapon is empty, press X to reload.".to_string());
+                                }
+                                continue;
+                            }
+                            magazine.current -= 1;
+                        }
+                    }
+                }
+
+                // Whether it hits or not, the shot rings out across the deck
+                MakesNoise::new_noise(&mut noises, entity, SHOT_NOISE);
+
+                if pattern == FiringPattern::Scoped {
+                    if aiming.get(entity).map_or(false, |aim| aim.target == wants_to_shoot.target) {
+                        accuracy += AIM_BONUS;
+                    }
+                    aiming.remove(entity);
+                }
+
+                // The firing pattern and whatever is in the line of fire decide who, if anyone, gets hit
+                let shooter_pos = Point::new(pos.x, pos.y);
+                let mut victims : Vec<(Entity, i32)> = Vec::new();
+                match pattern {
+                    FiringPattern::Single | FiringPattern::Scoped => {
+                        match line_of_fire::trace(&map, &blockers, shooter_pos, target_pos) {
+                            LineOfFire::Blocked { .. } => {
+                                log.entries.push(format!("{}'s shot hits the bulkhead.", &name.name));
+                                continue;
+                            }
+                            LineOfFire::Absorbed { by } => victims.push((by, 0)),
+                            LineOfFire::Clear { cover } => victims.push((wants_to_shoot.target, cover))
+                        }
+                    }
+                    FiringPattern::Spread { radius } => {
+                        for tile in line_of_fire::spread_area(&map, shooter_pos, target_pos, radius).iter() {
+                            for hit in map.tile_content[map.xy_idx(tile.x, tile.y)].iter() {
+                                if *hit != entity && combat_stats.get(*hit).is_some() {
+                                    victims.push((*hit, 0));
+                                }
+                            }
+                        }
+                    }
+                    FiringPattern::Beam => {
+                        for tile in line_of_fire::beam_path(&map, shooter_pos, target_pos, range).iter() {
+                            for hit in map.tile_content[map.xy_idx(tile.x, tile.y)].iter() {
+                                if *hit != entity && combat_stats.get(*hit).is_some() {
+                                    victims.push((*hit, 0));
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if victims.is_empty() {
+                    log.entries.push(format!("{}'s shot hits nothing.", &name.name));
+                }
+                for (victim, cover) in victims {
+                    if let Some(target_stats) = combat_stats.get(victim) {
+                        if target_stats.hp.current > 0 {
+                            let target_name = names.get(victim).unwrap();
+
+                            // Cover makes the target as hard to hit as a nimble one
+                            let roll = attack_roll::roll_attack(&mut rng, accuracy, target_stats.evasion + cover);
+                            let damage = attack_roll::apply_roll(roll, i32::max(0, range_power - target_stats.defense));
+
+                            if roll == AttackRoll::Miss && cover > 0 {
+                                log.entries.push(format!("{} misses {}, who is in cover.", &name.name, &target_name.name));
+                            } else if roll == AttackRoll::Miss {
+                                log.entries.push(format!("{} misses {}.", &name.name, &target_name.name));
+                            } else if damage == 0 {
+                                log.entries.push(format!(
+                                    "{} is unable to hurt {}",
+                                    &name.name, &target_name.name
+                                ));
+                            } else {
+                                log.entries.push(format!(
+                                    "{} {} {}, for {} {} damage.",
+                                    &name.name, attack_roll::hit_verb(roll), &target_name.name, damage, damage_type.name()
+                                ));
+                                SufferDamage::new_damage(
+                                    &mut inflict_damage,
+                                    victim,
+                                    damage,
+                                    damage_type,
+                                );
+                            }
+                        }
+                    }
                 }
-            } else {
-                log.entries.push(format!("You need energy to shoot!"))
             }
         }
 
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use super::SpawnRaw;
+use crate::{AmmoType, DamageType, EquipmentSlot, FiringPattern};
+
+#[derive(Deserialize, Clone)]
+pub struct RenderableRaw {
+    pub glyph : String,
+    pub fg : String,
+    pub bg : String,
+    pub render_order : i32
+}
+
+// Every component is optional, an item only gets the ones its entry lists
+#[derive(Deserialize, Clone)]
+pub struct ItemRaw {
+    pub name : String,
+    pub renderable : RenderableRaw,
+    // Left out for anything that never turns up at random
+    pub spawn : Option<SpawnRaw>,
+    #[serde(default)]
+    pub consumable : bool,
+    pub ranged : Option<i32>,
+    pub healing : Option<i32>,
+    pub oxygen : Option<i32>,
+    pub energy : Option<i32>,
+    pub power_output : Option<i32>,
+    pub damage : Option<DamageRaw>,
+    pub area_of_effect : Option<i32>,
+    pub confusion : Option<i32>,
+    pub hacking_bonus : Option<i32>,
+    #[serde(default)]
+    pub ammo_pack : bool,
+    #[serde(default)]
+    pub artefact : bool,
+    pub equippable : Option<EquipmentSlot>,
+    pub melee_bonus : Option<MeleeBonusRaw>,
+    pub ranged_weapon : Option<RangedWeaponRaw>,
+    pub defense_bonus : Option<i32>,
+    pub view_range_bonus : Option<i32>,
+    pub pool_bonus : Option<PoolBonusRaw>,
+    pub oxygen_efficiency : Option<i32>,
+    pub thermal_regulator : Option<i32>
+}
+
+#[derive(Deserialize, Clone)]
+pub struct DamageRaw {
+    pub amount : i32,
+    pub damage_type : DamageType
+}
+
+#[derive(Deserialize, Clone)]
+pub struct MeleeBonusRaw {
+    pub power : i32,
+    pub accuracy : i32,
+    pub damage_type : DamageType
+}
+
+// The magazine starts out full
+#[derive(Deserialize, Clone)]
+pub struct RangedWeaponRaw {
+    pub range : i32,
+    pub damage : i32,
+    pub accuracy : i32,
+    pub damage_type : DamageType,
+    pub pattern : FiringPattern,
+    pub magazine : i32,
+    pub ammo : AmmoType
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PoolBonusRaw {
+    pub hp : i32,
+    pub energy : i32,
+    pub oxygen : i32
+}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+// What a wreck can be stripped for, "None" is a valid drop that gives nothing
+#[derive(Deserialize, Clone)]
+pub struct LootTableRaw {
+    pub name : String,
+    pub drops : Vec<LootDropRaw>
+}
+
+#[derive(Deserialize, Clone)]
+pub struct LootDropRaw {
+    pub name : String,
+    pub weight : i32
+}
@@ -0,0 +1,50 @@
+use serde::Deserialize;
+mod item_structs;
+pub use item_structs::*;
+mod robot_structs;
+pub use robot_structs::*;
+mod loot_structs;
+pub use loot_structs::*;
+mod spawn_structs;
+pub use spawn_structs::*;
+mod prop_structs;
+pub use prop_structs::*;
+mod rawmaster;
+pub use rawmaster::*;
+
+// Baked into the binary so the web build doesn't have to fetch it
+const RAW_FILE : &str = include_str!("../../raws/spawns.json");
+
+#[derive(Deserialize, Clone)]
+pub struct Raws {
+    pub items : Vec<ItemRaw>,
+    pub robots : Vec<RobotRaw>,
+    pub props : Vec<PropRaw>,
+    pub loot_tables : Vec<LootTableRaw>
+}
+
+// Parses and checks the raws, a broken file stops the game right at startup instead of in the middle of a run
+pub fn load_raws() -> RawMaster {
+    let raws : Raws = match serde_json::from_str(RAW_FILE) {
+        Ok(raws) => raws,
+        Err(e) => panic!("Unable to parse raws/spawns.json: {}", e)
+    };
+    let master = RawMaster::new(raws);
+    let problems = master.validate();
+    if !problems.is_empty() {
+        panic!("raws/spawns.json has {} problem(s):\n{}", problems.len(), problems.join("\n"));
+    }
+    master
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_raws_are_valid() {
+        let raws : Raws = serde_json::from_str(RAW_FILE).expect("Unable to parse raws/spawns.json");
+        let problems = RawMaster::new(raws).validate();
+        assert!(problems.is_empty(), "raws/spawns.json has problems:\n{}", problems.join("\n"));
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use super::RenderableRaw;
+use crate::room_types::RoomType;
+
+// Furnishings left behind by the crew, they are only there to look at
+#[derive(Deserialize, Clone)]
+pub struct PropRaw {
+    pub name : String,
+    pub renderable : RenderableRaw,
+    pub rooms : Vec<RoomType>
+}
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use super::{Raws, ItemRaw, RobotRaw, PropRaw, RenderableRaw, SpawnRaw};
+use crate::random_table::RandomTable;
+use crate::deck_master::DECK_COUNT;
+use crate::room_types::RoomType;
+use rltk::RGB;
+
+// Spawned by name from the code, so the raws have to provide them
+const REQUIRED_ITEMS : [&str; 6] = ["Blaster", "Battery", "Basic Stim Pack", "Oxygen Tank", "Power Core", "Artefact from Yendoria"];
+const REQUIRED_ROBOTS : [&str; 2] = ["Security camera", "Fabricator"];
+
+// The loaded raws, indexed by name
+pub struct RawMaster {
+    raws : Raws,
+    item_index : HashMap<String, usize>,
+    robot_index : HashMap<String, usize>,
+    loot_index : HashMap<String, usize>,
+    prop_index : HashMap<String, usize>,
+    build_table : RandomTable
+}
+
+impl RawMaster {
+    pub fn new(raws : Raws) -> RawMaster {
+        let mut build_table = RandomTable::new();
+        let mut item_index = HashMap::new();
+        let mut robot_index = HashMap::new();
+        let mut loot_index = HashMap::new();
+        let mut prop_index = HashMap::new();
+
+        for (i, item) in raws.items.iter().enumerate() {
+            item_index.insert(item.name.clone(), i);
+        }
+        for (i, robot) in raws.robots.iter().enumerate() {
+            robot_index.insert(robot.name.clone(), i);
+            build_table = build_table.add(&robot.name, robot.build_weight);
+        }
+        for (i, loot) in raws.loot_tables.iter().enumerate() {
+            loot_index.insert(loot.name.clone(), i);
+        }
+        for (i, prop) in raws.props.iter().enumerate() {
+            prop_index.insert(prop.name.clone(), i);
+        }
+
+        RawMaster{ raws, item_index, robot_index, loot_index, prop_index, build_table }
+    }
+
+    pub fn item(&self, name : &str) -> Option<&ItemRaw> {
+        self.item_index.get(name).map(|i| &self.raws.items[*i])
+    }
+
+    pub fn robot(&self, name : &str) -> Option<&RobotRaw> {
+        self.robot_index.get(name).map(|i| &self.raws.robots[*i])
+    }
+
+    // What can turn up lying around on a deck, the deeper the better the gear. Rooms favour what they were used for.
+    pub fn item_table(&self, depth : i32, room_type : Option<RoomType>) -> RandomTable {
+        let mut table = RandomTable::new();
+        for item in self.raws.items.iter() {
+            if let Some(spawn) = &item.spawn {
+                table = table.add(&item.name, spawn.weight_at(depth, room_type));
+            }
+        }
+        table
+    }
+
+    // Who guards a deck, the deeper the more dangerous
+    pub fn hostile_table(&self, depth : i32, room_type : Option<RoomType>) -> RandomTable {
+        let mut table = RandomTable::new();
+        for robot in self.raws.robots.iter() {
+            if let Some(spawn) = &robot.spawn {
+                table = table.add(&robot.name, spawn.weight_at(depth, room_type));
+            }
+        }
+        table
+    }
+
+    pub fn prop(&self, name : &str) -> Option<&PropRaw> {
+        self.prop_index.get(name).map(|i| &self.raws.props[*i])
+    }
+
+    pub fn props_for(&self, room_type : RoomType) -> Vec<&PropRaw> {
+        self.raws.props.iter().filter(|prop| prop.rooms.contains(&room_type)).collect()
+    }
+
+    pub fn build_table(&self) -> &RandomTable {
+        &self.build_table
+    }
+
+    // An unknown table gives nothing at all
+    pub fn loot_table(&self, name : &str) -> RandomTable {
+        let mut table = RandomTable::new();
+        if let Some(i) = self.loot_index.get(name) {
+            for drop in self.raws.loot_tables[*i].drops.iter() {
+                table = table.add(&drop.name, drop.weight);
+            }
+        }
+        table
+    }
+
+    // Everything that would only go wrong once the entry is spawned, so it is caught at startup
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems : Vec<String> = Vec::new();
+        let mut names : HashSet<&str> = HashSet::new();
+
+        for item in self.raws.items.iter() {
+            if !names.insert(item.name.as_str()) {
+                problems.push(format!("{} is defined more than once", item.name));
+            }
+            check_renderable(&item.name, &item.renderable, &mut problems);
+            if let Some(spawn) = &item.spawn {
+                check_spawn(&item.name, spawn, &mut problems);
+            }
+            if (item.melee_bonus.is_some() || item.ranged_weapon.is_some()) && item.equippable.is_none() {
+                problems.push(format!("{} is a weapon but can't be equipped", item.name));
+            }
+        }
+
+        for robot in self.raws.robots.iter() {
+            if !names.insert(robot.name.as_str()) {
+                problems.push(format!("{} is defined more than once", robot.name));
+            }
+            check_renderable(&robot.name, &robot.renderable, &mut problems);
+            if let Some(spawn) = &robot.spawn {
+                check_spawn(&robot.name, spawn, &mut problems);
+            }
+            if robot.build_weight < 0 {
+                problems.push(format!("{} has a negative build weight", robot.name));
+            }
+            if robot.stats.hp < 1 {
+                problems.push(format!("{} starts out dead", robot.name));
+            }
+            if robot.brain && robot.speed.is_none() {
+                problems.push(format!("{} has a brain but no speed to act on it", robot.name));
+            }
+            if let Some(loot) = &robot.loot {
+                if !self.loot_index.contains_key(loot) {
+                    problems.push(format!("{} drops from loot table {}, which doesn't exist", robot.name, loot));
+                }
+            }
+        }
+
+        for prop in self.raws.props.iter() {
+            if !names.insert(prop.name.as_str()) {
+                problems.push(format!("{} is defined more than once", prop.name));
+            }
+            check_renderable(&prop.name, &prop.renderable, &mut problems);
+            if prop.rooms.is_empty() {
+                problems.push(format!("{} isn't placed in any room", prop.name));
+            }
+        }
+
+        for loot in self.raws.loot_tables.iter() {
+            for drop in loot.drops.iter() {
+                if drop.name != "None" && !self.item_index.contains_key(&drop.name) {
+                    problems.push(format!("Loot table {} drops {}, which isn't an item", loot.name, drop.name));
+                }
+            }
+        }
+
+        // Every deck needs something to find and something to fight
+        for depth in 1..=DECK_COUNT {
+            if self.item_table(depth, None).is_empty() {
+                problems.push(format!("No item can spawn on deck {}", depth));
+            }
+            if self.hostile_table(depth, None).is_empty() {
+                problems.push(format!("No robot can spawn on deck {}", depth));
+            }
+        }
+
+        for name in REQUIRED_ITEMS.iter() {
+            if !self.item_index.contains_key(*name) {
+                problems.push(format!("The item {} is missing", name));
+            }
+        }
+        for name in REQUIRED_ROBOTS.iter() {
+            if !self.robot_index.contains_key(*name) {
+                problems.push(format!("The robot {} is missing", name));
+            }
+        }
+
+        problems
+    }
+}
+
+fn check_spawn(name : &str, spawn : &SpawnRaw, problems : &mut Vec<String>) {
+    if spawn.weight < 0 {
+        problems.push(format!("{} has a negative spawn weight", name));
+    }
+    if spawn.min_depth < 1 || spawn.max_depth < spawn.min_depth {
+        problems.push(format!("{} spawns between decks {} and {}, which can't be", name, spawn.min_depth, spawn.max_depth));
+    }
+}
+
+fn check_renderable(name : &str, renderable : &RenderableRaw, problems : &mut Vec<String>) {
+    let mut chars = renderable.glyph.chars();
+    match (chars.next(), chars.next()) {
+        (Some(glyph), None) => {
+            if rltk::to_cp437(glyph) == 0 {
+                problems.push(format!("{} uses glyph {}, which the font can't draw", name, glyph));
+            }
+        }
+        _ => problems.push(format!("{} needs exactly one glyph, not \"{}\"", name, renderable.glyph))
+    }
+    for colour in [&renderable.fg, &renderable.bg].iter() {
+        if RGB::from_hex(colour).is_err() {
+            problems.push(format!("{} has colour {}, which isn't a #RRGGBB code", name, colour));
+        }
+    }
+}
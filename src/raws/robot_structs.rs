@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use super::{RenderableRaw, SpawnRaw};
+use crate::{DamageType, FactionId};
+
+#[derive(Deserialize, Clone)]
+pub struct RobotRaw {
+    pub name : String,
+    pub renderable : RenderableRaw,
+    pub spawn : Option<SpawnRaw>,
+    // How likely a fabricator is to build one of these
+    #[serde(default)]
+    pub build_weight : i32,
+    pub stats : StatsRaw,
+    pub view_range : i32,
+    // Without a speed it never gets a turn of its own
+    pub speed : Option<i32>,
+    pub faction : FactionId,
+    #[serde(default)]
+    pub resistances : ResistancesRaw,
+    #[serde(default)]
+    pub robot : bool,
+    #[serde(default)]
+    pub brain : bool,
+    #[serde(default)]
+    pub opens_doors : bool,
+    #[serde(default)]
+    pub immobile : bool,
+    pub gun : Option<GunRaw>,
+    #[serde(default)]
+    pub security_camera : bool,
+    pub fabricator : Option<FabricatorRaw>,
+    pub loot : Option<String>
+}
+
+// Robots on lower decks are tougher and hit harder, the per_depth values are added for every deck below the first
+#[derive(Deserialize, Clone)]
+pub struct StatsRaw {
+    pub hp : i32,
+    #[serde(default)]
+    pub hp_per_depth : i32,
+    pub defense : i32,
+    pub evasion : i32,
+    pub power : i32,
+    #[serde(default)]
+    pub power_per_depth : i32
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ResistancesRaw {
+    pub kinetic : i32,
+    pub energy : i32,
+    pub thermal : i32,
+    pub emp : i32
+}
+
+// A built in gun, its energy pool is the ammunition
+#[derive(Deserialize, Clone)]
+pub struct GunRaw {
+    pub range : i32,
+    pub damage : i32,
+    #[serde(default)]
+    pub damage_per_depth : i32,
+    pub accuracy : i32,
+    pub damage_type : DamageType,
+    pub energy : i32
+}
+
+// The build interval shrinks on every deck below the first, down to min_interval
+#[derive(Deserialize, Clone)]
+pub struct FabricatorRaw {
+    pub interval : i32,
+    #[serde(default)]
+    pub faster_per_depth : i32,
+    pub min_interval : i32
+}
@@ -0,0 +1,84 @@
+use serde::Deserialize;
+use crate::room_types::RoomType;
+
+// Where and how often something turns up in a room. The weight shifts by weight_per_depth
+// for every deck below min_depth and by the bonus of the room it is rolled for, it never drops below zero.
+#[derive(Deserialize, Clone)]
+pub struct SpawnRaw {
+    pub weight : i32,
+    #[serde(default)]
+    pub weight_per_depth : i32,
+    #[serde(default = "first_deck")]
+    pub min_depth : i32,
+    #[serde(default = "last_deck")]
+    pub max_depth : i32,
+    #[serde(default)]
+    pub rooms : Vec<RoomBonusRaw>
+}
+
+#[derive(Deserialize, Clone)]
+pub struct RoomBonusRaw {
+    pub room : RoomType,
+    pub weight : i32
+}
+
+impl SpawnRaw {
+    pub fn weight_at(&self, depth : i32, room_type : Option<RoomType>) -> i32 {
+        if depth < self.min_depth || depth > self.max_depth {
+            return 0;
+        }
+        let room_bonus : i32 = self.rooms.iter()
+            .filter(|bonus| Some(bonus.room) == room_type)
+            .map(|bonus| bonus.weight)
+            .sum();
+        i32::max(0, self.weight + room_bonus + (depth - self.min_depth) * self.weight_per_depth)
+    }
+}
+
+fn first_deck() -> i32 {
+    1
+}
+
+fn last_deck() -> i32 {
+    i32::MAX
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn(weight : i32, weight_per_depth : i32, min_depth : i32, max_depth : i32, rooms : Vec<RoomBonusRaw>) -> SpawnRaw {
+        SpawnRaw{ weight, weight_per_depth, min_depth, max_depth, rooms }
+    }
+
+    #[test]
+    fn nothing_outside_the_depth_range() {
+        let raw = spawn(10, 0, 2, 4, Vec::new());
+        assert_eq!(raw.weight_at(1, None), 0);
+        assert_eq!(raw.weight_at(2, None), 10);
+        assert_eq!(raw.weight_at(4, None), 10);
+        assert_eq!(raw.weight_at(5, None), 0);
+    }
+
+    #[test]
+    fn weight_shifts_with_depth() {
+        let raw = spawn(10, 3, 2, last_deck(), Vec::new());
+        assert_eq!(raw.weight_at(2, None), 10);
+        assert_eq!(raw.weight_at(4, None), 16);
+    }
+
+    #[test]
+    fn room_bonus_only_in_its_room() {
+        let raw = spawn(10, 0, first_deck(), last_deck(), vec![RoomBonusRaw{ room: RoomType::Armory, weight: 5 }]);
+        assert_eq!(raw.weight_at(1, Some(RoomType::Armory)), 15);
+        assert_eq!(raw.weight_at(1, Some(RoomType::Medbay)), 10);
+        assert_eq!(raw.weight_at(1, None), 10);
+    }
+
+    #[test]
+    fn weight_never_drops_below_zero() {
+        let raw = spawn(4, -3, first_deck(), last_deck(), vec![RoomBonusRaw{ room: RoomType::Bridge, weight: -10 }]);
+        assert_eq!(raw.weight_at(1, Some(RoomType::Bridge)), 0);
+        assert_eq!(raw.weight_at(3, None), 0);
+    }
+}
@@ -21,4 +21,9 @@ impl Rect {
     pub fn center(&self) -> (i32, i32) {
         ((self.x1 + self.x2)/2, (self.y1 + self.y2)/2)
     }
+
+    // The outer edge is the room's wall, so only the tiles inside it count
+    pub fn contains(&self, x:i32, y:i32) -> bool {
+        x > self.x1 && x <= self.x2 && y > self.y1 && y <= self.y2
+    }
 }
\ No newline at end of file
@@ -1,6 +1,23 @@
 use specs::prelude::*;
-use super::{Viewshed, Robot, Map, Position, WantsToMelee, RunState, Confusion};
-use rltk::{Point};
+use super::{Viewshed, Robot, Map, Position, WantsToMelee, RunState, Confusion, TileType, CanOpenDoors, CombatStats, BlocksTile, Item, WantsToPickupItem};
+use crate::{AiState, Brain, Companion, Faction, Immobile, Initiative, RangedWeapon, WantsToShoot};
+use crate::initiative_system::TURN_COST;
+use crate::line_of_fire::{self, LineOfFire};
+use crate::faction;
+use rltk::{Point, RandomNumberGenerator};
+
+// Gunners back off when their target gets closer than this
+const KEEP_DISTANCE : f32 = 3.0;
+// Robots below this share of their health in percent break off and head for a repair station
+const FLEE_PERCENT : i32 = 30;
+// How long a robot searches around where it last saw its target before going back to its patrol
+pub const SEARCH_TURNS : i32 = 12;
+// Health a docked robot gets back every turn
+const REPAIR_RATE : i32 = 2;
+// Robots on or right next to a repair station can plug into it
+const REPAIR_REACH : f32 = 1.5;
+// Companions catch up with the salvager once they fall further behind than this
+const FOLLOW_DISTANCE : f32 = 2.5;
 
 pub struct RobotAI {}
 
@@ -9,33 +26,72 @@ impl <'a> System<'a> for RobotAI {
     type SystemData = (
         WriteExpect<'a, Map>,
         ReadExpect<'a, Point>,
-        ReadExpect<'a, Entity>,
         ReadExpect<'a, RunState>,
         Entities<'a>,
         WriteStorage<'a, Viewshed>,
         ReadStorage<'a, Robot>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, WantsToMelee>,
-        WriteStorage<'a, Confusion>
+        WriteStorage<'a, Confusion>,
+        ReadStorage<'a, CanOpenDoors>,
+        WriteStorage<'a, CombatStats>,
+        ReadStorage<'a, RangedWeapon>,
+        WriteStorage<'a, WantsToShoot>,
+        ReadStorage<'a, Immobile>,
+        ReadStorage<'a, BlocksTile>,
+        WriteStorage<'a, Brain>,
+        ReadStorage<'a, Faction>,
+        WriteStorage<'a, Initiative>,
+        ReadStorage<'a, Companion>,
+        ReadStorage<'a, Item>,
+        WriteStorage<'a, WantsToPickupItem>
     );
 
     fn run(&mut self, data : Self::SystemData) {
         let (
             mut map, 
             player_pos, 
-            player_entity, 
             _runstate, 
             entities, 
             mut viewshed, 
             robots, 
             mut position, 
             mut wants_to_melee, 
-            mut confused
+            mut confused,
+            door_openers,
+            mut combat_stats,
+            ranged_weapons,
+            mut wants_to_shoot,
+            immobile,
+            blockers,
+            mut brains,
+            factions,
+            mut initiatives,
+            companions,
+            items,
+            mut wants_pickup
         ) = data;
 
         if *_runstate != RunState::MonsterTurn { return }
 
-        for (entity, mut viewshed,_robot,mut pos) in (&entities, &mut viewshed, &robots, &mut position).join() {
+        let mut closed_doors : Vec<usize> = Vec::new();
+        let mut repair_stations : Vec<Point> = Vec::new();
+        for (idx, tile) in map.tiles.iter().enumerate() {
+            if *tile == TileType::DoorClosed {
+                closed_doors.push(idx);
+            }
+            if *tile == TileType::RepairStation {
+                repair_stations.push(Point::new(idx as i32 % map.width, idx as i32 / map.width));
+            }
+        }
+
+        for (entity, mut viewshed, mut pos, brain) in (&entities, &mut viewshed, &mut position, &mut brains).join() {
+            // Only those with an action banked get to move this pass
+            if let Some(initiative) = initiatives.get_mut(entity) {
+                if initiative.energy < 0 { continue; }
+                initiative.energy -= TURN_COST;
+            }
+
             let mut can_act = true;
 
             let is_confused = confused.get_mut(entity);
@@ -48,28 +104,221 @@ impl <'a> System<'a> for RobotAI {
             }
 
             if can_act {
-                let distance = rltk::DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), *player_pos);
-                if distance < 1.5 {
-                    wants_to_melee.insert(entity, WantsToMelee{ target: *player_entity }).expect("Unable to insert attack");
+                let my_pos = Point::new(pos.x, pos.y);
+                let can_move = immobile.get(entity).is_none();
+
+                // Go for the closest thing in sight that belongs to a hostile faction
+                let mut target : Option<(Entity, Point, f32)> = None;
+                for tile in viewshed.visible_tiles.iter() {
+                    for other in map.tile_content[map.xy_idx(tile.x, tile.y)].iter() {
+                        let alive = combat_stats.get(*other).map_or(false, |stats| stats.hp.current > 0);
+                        if *other == entity || !alive || !faction::is_hostile(factions.get(entity), factions.get(*other)) { continue; }
+                        let distance = rltk::DistanceAlg::Pythagoras.distance2d(my_pos, *tile);
+                        if target.map_or(true, |(_, _, closest)| distance < closest) {
+                            target = Some((*other, *tile, distance));
+                        }
+                    }
                 }
-                else if viewshed.visible_tiles.contains(&*player_pos) {
-                    // Path to the player
-                    let path = rltk::a_star_search(
-                        map.xy_idx(pos.x, pos.y),
-                        map.xy_idx(player_pos.x, player_pos.y),
-                        &mut *map
-                    );
-                    if path.success && path.steps.len()>1 {
-                        let mut idx = map.xy_idx(pos.x, pos.y);
-                        map.blocked_tiles[idx] = false;
-                        pos.x = path.steps[1] as i32 % map.width;
-                        pos.y = path.steps[1] as i32 / map.width;
-                        idx = map.xy_idx(pos.x, pos.y);
-                        map.blocked_tiles[idx] = true;
-                        viewshed.dirty = true;
+
+                // Robots that can work a powered door plan their path straight through closed ones
+                let opens_doors = door_openers.get(entity).is_some() && map.power.doors;
+
+                // Badly damaged robots run for repairs and don't stop until they are whole again
+                let can_repair = robots.get(entity).is_some() && !repair_stations.is_empty();
+                let fleeing = can_move && can_repair && match combat_stats.get(entity) {
+                    Some(stats) if brain.state == AiState::Flee => stats.hp.current < stats.hp.max,
+                    Some(stats) => stats.hp.current * 100 < stats.hp.max * FLEE_PERCENT,
+                    None => false
+                };
+                if fleeing {
+                    brain.state = AiState::Flee;
+                } else if let Some((_, target_pos, _)) = target {
+                    brain.state = AiState::Chase;
+                    brain.last_seen = Some(target_pos);
+                } else if brain.state == AiState::Chase {
+                    brain.state = AiState::Investigate;
+                    brain.search_turns = SEARCH_TURNS;
+                } else if brain.state == AiState::Flee {
+                    brain.state = AiState::Patrol;
+                }
+
+                // Companions stay with the salvager and carry any salvage they find while there is nothing to fight
+                if companions.get(entity).is_some() && (brain.state == AiState::Patrol || brain.state == AiState::Investigate) {
+                    brain.state = AiState::Patrol;
+                    if can_move && rltk::DistanceAlg::Pythagoras.distance2d(my_pos, *player_pos) > FOLLOW_DISTANCE {
+                        move_towards(&mut map, pos, viewshed, *player_pos, opens_doors, &mut closed_doors);
+                    } else if let Some(item) = map.tile_content[map.xy_idx(pos.x, pos.y)].iter().find(|other| items.get(**other).is_some()) {
+                        wants_pickup.insert(entity, WantsToPickupItem{ collected_by: entity, item: *item }).expect("Unable to insert want to pickup");
+                    }
+                    continue;
+                }
+
+                if brain.state == AiState::Flee {
+                    let station = repair_stations.iter()
+                        .min_by(|a, b| {
+                            rltk::DistanceAlg::Pythagoras.distance2d(my_pos, **a)
+                                .partial_cmp(&rltk::DistanceAlg::Pythagoras.distance2d(my_pos, **b))
+                                .unwrap()
+                        })
+                        .copied()
+                        .unwrap();
+                    if rltk::DistanceAlg::Pythagoras.distance2d(my_pos, station) < REPAIR_REACH {
+                        if let Some(stats) = combat_stats.get_mut(entity) {
+                            stats.hp.current = i32::min(stats.hp.max, stats.hp.current + REPAIR_RATE);
+                        }
+                        continue;
+                    }
+                    if move_towards(&mut map, pos, viewshed, station, opens_doors, &mut closed_doors) {
+                        continue;
+                    }
+                    // The way to the station is blocked, so fight or patrol this turn and try again on the next
+                    if let Some((_, target_pos, _)) = target {
+                        brain.state = AiState::Chase;
+                        brain.last_seen = Some(target_pos);
+                    } else {
+                        brain.state = AiState::Patrol;
+                    }
+                }
+
+                match brain.state {
+                    // Handled above, a fleeing robot that gets here has already dropped out of it
+                    AiState::Flee => {}
+                    AiState::Chase => {
+                        let (target_entity, target_pos, distance) = match target {
+                            Some(target) => target,
+                            None => continue
+                        };
+
+                        // Armed robots with charge left fight from a distance, as long as nothing is in the way
+                        let mut gun_range = None;
+                        if let (Some(weapon), Some(stats)) = (ranged_weapons.get(entity), combat_stats.get(entity)) {
+                            if stats.energy.current > 0 && distance <= weapon.range as f32 {
+                                if let LineOfFire::Clear{ .. } = line_of_fire::trace(&map, &blockers, my_pos, target_pos) {
+                                    gun_range = Some(weapon.range as f32);
+                                }
+                            }
+                        }
+
+                        if let Some(range) = gun_range {
+                            let retreat = if can_move && distance < KEEP_DISTANCE && range > KEEP_DISTANCE {
+                                retreat_step(&map, pos.x, pos.y, target_pos, distance)
+                            } else {
+                                None
+                            };
+                            if let Some(step) = retreat {
+                                let mut idx = map.xy_idx(pos.x, pos.y);
+                                map.blocked_tiles[idx] = false;
+                                pos.x = step.x;
+                                pos.y = step.y;
+                                idx = map.xy_idx(pos.x, pos.y);
+                                map.blocked_tiles[idx] = true;
+                                viewshed.dirty = true;
+                            } else {
+                                wants_to_shoot.insert(entity, WantsToShoot{ target: target_entity }).expect("Unable to insert shot");
+                            }
+                        }
+                        else if distance < 1.5 {
+                            wants_to_melee.insert(entity, WantsToMelee{ target: target_entity }).expect("Unable to insert attack");
+                        }
+                        else if can_move {
+                            move_towards(&mut map, pos, viewshed, target_pos, opens_doors, &mut closed_doors);
+                        }
+                    }
+                    AiState::Investigate => {
+                        // Head for where the target was last seen, and give up if it isn't there
+                        brain.search_turns -= 1;
+                        let done_searching = match brain.last_seen {
+                            Some(spot) => !can_move || my_pos == spot
+                                || !move_towards(&mut map, pos, viewshed, spot, opens_doors, &mut closed_doors),
+                            None => true
+                        };
+                        if done_searching || brain.search_turns <= 0 {
+                            brain.state = AiState::Patrol;
+                            brain.last_seen = None;
+                        }
+                    }
+                    AiState::Patrol => {
+                        // Walk the route, skipping any stop that can't be reached right now
+                        if can_move && !brain.patrol_route.is_empty() {
+                            let waypoint = brain.patrol_route[brain.next_waypoint % brain.patrol_route.len()];
+                            if my_pos == waypoint || !move_towards(&mut map, pos, viewshed, waypoint, opens_doors, &mut closed_doors) {
+                                brain.next_waypoint = (brain.next_waypoint + 1) % brain.patrol_route.len();
+                            }
+                        }
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// Takes one step along the path to the target, opening a closed door on the way takes the whole turn.
+// Returns false if there is no way to get there.
+fn move_towards(map : &mut Map, pos : &mut Position, viewshed : &mut Viewshed, target : Point, opens_doors : bool, closed_doors : &mut Vec<usize>) -> bool {
+    if opens_doors {
+        for idx in closed_doors.iter() { map.blocked_tiles[*idx] = false; }
+    }
+
+    let path = rltk::a_star_search(
+        map.xy_idx(pos.x, pos.y),
+        map.xy_idx(target.x, target.y),
+        &mut *map
+    );
+
+    if opens_doors {
+        for idx in closed_doors.iter() { map.blocked_tiles[*idx] = true; }
+    }
+
+    if !path.success || path.steps.len() < 2 {
+        return false;
+    }
+
+    if map.tiles[path.steps[1]] == TileType::DoorClosed {
+        let door_idx = path.steps[1];
+        map.tiles[door_idx] = TileType::DoorOpen;
+        map.blocked_tiles[door_idx] = false;
+        closed_doors.retain(|idx| *idx != door_idx);
+    } else {
+        let mut idx = map.xy_idx(pos.x, pos.y);
+        map.blocked_tiles[idx] = false;
+        pos.x = path.steps[1] as i32 % map.width;
+        pos.y = path.steps[1] as i32 / map.width;
+        idx = map.xy_idx(pos.x, pos.y);
+        map.blocked_tiles[idx] = true;
+    }
+    viewshed.dirty = true;
+    true
+}
+
+// Every deck has a repair station in one of its rooms where damaged robots patch themselves up
+pub fn install_repair_station(map : &mut Map, rng : &mut RandomNumberGenerator) {
+    if map.rooms.len() < 2 { return; }
+    for _try in 0..10 {
+        let room = map.rooms[rng.range(1, map.rooms.len())];
+        let (x, y) = room.center();
+        let idx = map.xy_idx(x, y);
+        if map.tiles[idx] == TileType::Floor && map.heat_sources[idx] == 0 {
+            map.tiles[idx] = TileType::RepairStation;
+            return;
+        }
+    }
+}
+
+// Finds the neighbouring tile that gets the robot furthest away from its target, if any is better than standing still
+fn retreat_step(map : &Map, x : i32, y : i32, threat : Point, distance : f32) -> Option<Point> {
+    let mut best : Option<Point> = None;
+    let mut best_distance = distance;
+    for delta_y in -1 ..= 1 {
+        for delta_x in -1 ..= 1 {
+            let (nx, ny) = (x + delta_x, y + delta_y);
+            if nx < 1 || nx > map.width-1 || ny < 1 || ny > map.height-1 { continue; }
+            if map.blocked_tiles[map.xy_idx(nx, ny)] { continue; }
+            let new_distance = rltk::DistanceAlg::Pythagoras.distance2d(Point::new(nx, ny), threat);
+            if new_distance > best_distance {
+                best_distance = new_distance;
+                best = Some(Point::new(nx, ny));
+            }
+        }
+    }
+    best
+}
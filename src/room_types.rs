@@ -0,0 +1,43 @@
+use super::Map;
+use rltk::RandomNumberGenerator;
+use serde::{Serialize, Deserialize};
+
+// What a room was used for before the ship was lost, it decides how it is furnished and what is left in it
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum RoomType { Bridge, Medbay, EngineRoom, CargoHold, CrewQuarters, Armory }
+
+impl RoomType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoomType::Bridge => "Bridge",
+            RoomType::Medbay => "Medbay",
+            RoomType::EngineRoom => "Engine room",
+            RoomType::CargoHold => "Cargo hold",
+            RoomType::CrewQuarters => "Crew quarters",
+            RoomType::Armory => "Armory"
+        }
+    }
+}
+
+// The salvager cuts in through a cargo hold. Every other deck has one bridge and one engine room,
+// the rest of the rooms get a random role.
+pub fn assign_room_types(map : &mut Map, rng : &mut RandomNumberGenerator) {
+    let mut room_types = vec![RoomType::CargoHold; map.rooms.len()];
+    let mut unassigned : Vec<usize> = (1..map.rooms.len()).collect();
+
+    for room_type in [RoomType::Bridge, RoomType::EngineRoom].iter() {
+        if unassigned.is_empty() { break; }
+        let pick = rng.range(0, unassigned.len());
+        room_types[unassigned.remove(pick)] = *room_type;
+    }
+    for i in unassigned {
+        room_types[i] = match rng.roll_dice(1, 4) {
+            1 => RoomType::Medbay,
+            2 => RoomType::CrewQuarters,
+            3 => RoomType::Armory,
+            _ => RoomType::CargoHold
+        };
+    }
+
+    map.room_types = room_types;
+}
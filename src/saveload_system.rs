@@ -26,9 +26,12 @@ macro_rules! serialize_individually {
 pub fn save_game(ecs: &mut World) {
     // Create helper
     let mapcopy = ecs.get_mut::<super::map::Map>().unwrap().clone();
+    let seedcopy = ecs.get_mut::<super::game_seed::GameSeed>().unwrap().clone();
+    let rngcopy = ecs.get_mut::<rltk::RandomNumberGenerator>().unwrap().clone();
+    let deckscopy = ecs.get_mut::<super::deck_master::DeckMaster>().unwrap().clone();
     let savehelper = ecs
         .create_entity()
-        .with(SerializationHelper { map: mapcopy })
+        .with(SerializationHelper { map: mapcopy, seed: seedcopy, rng: rngcopy, decks: deckscopy })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 
@@ -77,7 +80,35 @@ pub fn save_game(ecs: &mut World) {
             BreathOxygen,
             ProvidesOxygen,
             ProvidesEnergy,
-            ArtefactFromYendoria
+            ArtefactFromYendoria,
+            OtherDeckPosition,
+            CanOpenDoors,
+            PowerCore,
+            BaseStats,
+            PoolBonus,
+            DefenseBonus,
+            ViewRangeBonus,
+            OxygenEfficiency,
+            Exertion,
+            WantsToRest,
+            ThermalRegulator,
+            Immobile,
+            Resistances,
+            Magazine,
+            AmmoPack,
+            Aiming,
+            LootTable,
+            Wreckage,
+            Brain,
+            MakesNoise,
+            Faction,
+            Initiative,
+            TimeCost,
+            Companion,
+            WantsToHack,
+            HackingTool,
+            SecurityCamera,
+            Fabricator
         );
     }
 
@@ -165,7 +196,35 @@ pub fn load_game(ecs: &mut World) {
             BreathOxygen,
             ProvidesOxygen,
             ProvidesEnergy,
-            ArtefactFromYendoria
+            ArtefactFromYendoria,
+            OtherDeckPosition,
+            CanOpenDoors,
+            PowerCore,
+            BaseStats,
+            PoolBonus,
+            DefenseBonus,
+            ViewRangeBonus,
+            OxygenEfficiency,
+            Exertion,
+            WantsToRest,
+            ThermalRegulator,
+            Immobile,
+            Resistances,
+            Magazine,
+            AmmoPack,
+            Aiming,
+            LootTable,
+            Wreckage,
+            Brain,
+            MakesNoise,
+            Faction,
+            Initiative,
+            TimeCost,
+            Companion,
+            WantsToHack,
+            HackingTool,
+            SecurityCamera,
+            Fabricator
         );
     }
 
@@ -179,6 +238,13 @@ pub fn load_game(ecs: &mut World) {
             let mut worldmap = ecs.write_resource::<super::map::Map>();
             *worldmap = h.map.clone();
             worldmap.tile_content = vec![Vec::new(); super::map::MAP_COUNT];
+            let mut game_seed = ecs.write_resource::<super::game_seed::GameSeed>();
+            *game_seed = h.seed.clone();
+            // Pick the generator up exactly where the saved run left it, so the seed still replays the run
+            let mut rng = ecs.write_resource::<rltk::RandomNumberGenerator>();
+            *rng = h.rng.clone();
+            let mut decks = ecs.write_resource::<super::deck_master::DeckMaster>();
+            *decks = h.decks.clone();
             deleteme = Some(e);
         }
         for (e, _p, pos) in (&entities, &player, &position).join() {
@@ -1,65 +1,152 @@
 use super::{
-    map::MAP_WIDTH, AreaOfEffect, BlocksTile, CombatStats, Confusion, Consumable, InflictsDamage,
+    Map, TileType, AreaOfEffect, BlocksTile, CombatStats, Confusion, Consumable, InflictsDamage,
     Item, Name, Player, Position, ProvidesHealing, Ranged, Rect, Renderable, Robot, SerializeMe,
     Viewshed,
 };
-use crate::{EquipmentSlot, Equippable, MeleePowerBonus, Pool, RangedWeapon, ProvidesOxygen, ProvidesEnergy, ArtefactFromYendoria};
-use rltk::{RandomNumberGenerator, RGB};
+use crate::{Equippable, MeleePowerBonus, Pool, RangedWeapon, ProvidesOxygen, ProvidesEnergy, ArtefactFromYendoria, CanOpenDoors, PowerCore,
+            BaseStats, PoolBonus, DefenseBonus, ViewRangeBonus, OxygenEfficiency, ThermalRegulator, Immobile,
+            Resistances, Magazine, AmmoPack, FiringPattern, LootTable, Wreckage,
+            AiState, Brain, Faction, FactionId, Initiative, HackingTool, SecurityCamera, Fabricator};
+use crate::initiative_system::TURN_COST;
+use crate::raws::{RawMaster, RenderableRaw};
+use crate::room_types::RoomType;
+use rltk::{Point, RandomNumberGenerator, RGB};
 use specs::prelude::*;
 use specs::saveload::{MarkedBuilder, SimpleMarker};
 
-const MAX_ROBOTS: i32 = 4;
-const MAX_ITEMS: i32 = 2;
+// How many times a wreck's loot table is rolled when it is stripped
+const SALVAGE_ROLLS: i32 = 2;
+// Rooms a robot visits on its patrol besides the one it starts in
+const PATROL_STOPS: i32 = 2;
+// Percent chance for a room to have a security camera watching it
+const CAMERA_CHANCE: i32 = 35;
+const MAX_PROPS: i32 = 3;
 
-pub fn spawn_room(ecs: &mut World, room: &Rect) {
-    let mut robots_spawn_points: Vec<usize> = Vec::new();
-    let mut item_spawn_points: Vec<usize> = Vec::new();
+pub fn spawn_room(ecs: &mut World, room: &Rect, room_type: RoomType, depth: i32) {
+    let mut robots_spawn_points: Vec<Point> = Vec::new();
+    let mut item_spawn_points: Vec<Point> = Vec::new();
 
     // Scope to keep the borrow checker happy
     {
         let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let map = ecs.fetch::<Map>();
+        // Furnishings are already in place and robots shouldn't end up inside them
+        let mut taken = occupied_tiles(ecs);
 
-        let num_robots = rng.roll_dice(1, MAX_ROBOTS + 2) - 3;
+        let num_robots = rng.roll_dice(1, max_robots(depth) + 2) - 3;
         for _i in 0..num_robots {
-            let mut added = false;
-            while !added {
-                let x = (room.x1 + rng.roll_dice(1, i32::abs(room.x2 - room.x1))) as usize;
-                let y = (room.y1 + rng.roll_dice(1, i32::abs(room.y2 - room.y1))) as usize;
-                let idx = (y * MAP_WIDTH) + x;
-                if !robots_spawn_points.contains(&idx) {
-                    robots_spawn_points.push(idx);
-                    added = true;
-                }
+            if let Some(spot) = random_floor_tile(&mut rng, &map, room, &taken) {
+                robots_spawn_points.push(spot);
+                taken.push(spot);
             }
         }
 
-        let num_items = rng.roll_dice(1, MAX_ITEMS + 2) - 3;
+        let num_items = rng.roll_dice(1, max_items(depth) + 2) - 3;
         for _i in 0..num_items {
-            let mut added = false;
-            while !added {
-                let x = (room.x1 + rng.roll_dice(1, i32::abs(room.x2 - room.x1))) as usize;
-                let y = (room.y1 + rng.roll_dice(1, i32::abs(room.y2 - room.y1))) as usize;
-                let idx = (y * MAP_WIDTH) + x;
-                if !item_spawn_points.contains(&idx) {
-                    item_spawn_points.push(idx);
-                    added = true;
-                }
+            if let Some(spot) = random_floor_tile(&mut rng, &map, room, &taken) {
+                item_spawn_points.push(spot);
+                taken.push(spot);
             }
         }
     }
 
     // Actually spawn the robots
-    for idx in robots_spawn_points.iter() {
-        let x = *idx % MAP_WIDTH;
-        let y = *idx / MAP_WIDTH;
-        random_robot(ecs, x as i32, y as i32);
+    for spot in robots_spawn_points.iter() {
+        random_hostile(ecs, spot.x, spot.y, depth, room_type);
     }
 
     // Actually spawn the stim packs
-    for idx in item_spawn_points.iter() {
-        let x = *idx % MAP_WIDTH;
-        let y = *idx / MAP_WIDTH;
-        random_item(ecs, x as i32, y as i32);
+    for spot in item_spawn_points.iter() {
+        random_item(ecs, spot.x, spot.y, depth, room_type);
+    }
+}
+
+// Picks a free floor tile inside the room. Rooms of open layouts aren't all floor, so it gives up after a few misses.
+fn random_floor_tile(rng: &mut RandomNumberGenerator, map: &Map, room: &Rect, taken: &[Point]) -> Option<Point> {
+    for _try in 0..20 {
+        let spot = Point::new(
+            room.x1 + rng.roll_dice(1, i32::abs(room.x2 - room.x1)),
+            room.y1 + rng.roll_dice(1, i32::abs(room.y2 - room.y1)),
+        );
+        if map.tiles[map.xy_idx(spot.x, spot.y)] == TileType::Floor && !taken.contains(&spot) {
+            return Some(spot);
+        }
+    }
+    None
+}
+
+// Everything already placed on the deck, the map isn't indexed until the deck is finished
+fn occupied_tiles(ecs: &World) -> Vec<Point> {
+    ecs.read_storage::<Position>().join().map(|pos| Point::new(pos.x, pos.y)).collect()
+}
+
+// Rooms get more crowded the deeper the salvager goes
+fn max_robots(depth: i32) -> i32 {
+    4 + (depth - 1) * 2
+}
+
+// Deeper decks were stripped less by earlier salvagers
+fn max_items(depth: i32) -> i32 {
+    2 + (depth - 1) / 2
+}
+
+// Scatters a few of the furnishings that fit the room's role, the center is left free for whatever is placed there
+pub fn furnish_room(ecs: &mut World, room: &Rect, room_type: RoomType) {
+    let mut props: Vec<(String, i32, i32)> = Vec::new();
+
+    // Scope to keep the borrow checker happy
+    {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let map = ecs.fetch::<Map>();
+        let raws = ecs.fetch::<RawMaster>();
+        let choices = raws.props_for(room_type);
+        if choices.is_empty() {
+            return;
+        }
+
+        let (center_x, center_y) = room.center();
+        let mut taken = occupied_tiles(ecs);
+        taken.push(Point::new(center_x, center_y));
+        let num_props = rng.roll_dice(1, MAX_PROPS);
+        for _i in 0..num_props {
+            let prop = choices[rng.range(0, choices.len())];
+            if let Some(spot) = random_floor_tile(&mut rng, &map, room, &taken) {
+                props.push((prop.name.clone(), spot.x, spot.y));
+                taken.push(spot);
+            }
+        }
+    }
+
+    for (name, x, y) in props.iter() {
+        spawn_named_prop(ecs, name, *x, *y);
+    }
+}
+
+// Every deck holds one or two power cores that can feed its grid
+pub fn spawn_power_cores(ecs: &mut World, rooms: &[Rect]) {
+    if rooms.is_empty() {
+        return;
+    }
+    let mut core_spawn_points: Vec<Point> = Vec::new();
+
+    // Scope to keep the borrow checker happy
+    {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let map = ecs.fetch::<Map>();
+        let mut taken = occupied_tiles(ecs);
+
+        let num_cores = rng.roll_dice(1, 2);
+        for _i in 0..num_cores {
+            let room = rooms[(rng.roll_dice(1, rooms.len() as i32) - 1) as usize];
+            if let Some(spot) = random_floor_tile(&mut rng, &map, &room, &taken) {
+                core_spawn_points.push(spot);
+                taken.push(spot);
+            }
+        }
+    }
+
+    for spot in core_spawn_points.iter() {
+        spawn_named_item(ecs, "Power Core", spot.x, spot.y);
     }
 }
 
@@ -84,6 +171,14 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
         .with(Name {
             name: "Player".to_string(),
         })
+        .with(Faction {
+            id: FactionId::Salvagers,
+        })
+        // The player has the opening move, everyone else starts a turn behind
+        .with(Initiative {
+            speed: 100,
+            energy: 0,
+        })
         .with(CombatStats {
             hp: Pool {
                 current: 30,
@@ -100,61 +195,97 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
                 max: 100,
                 name: "Oxygen".to_string(),
             },
+            stamina: Pool {
+                current: 20,
+                max: 20,
+                name: "Stamina".to_string(),
+            },
+            heat: Pool {
+                current: 50,
+                max: 100,
+                name: "Suit heat".to_string(),
+            },
             defense: 2,
+            evasion: 10,
             power: 5,
         })
+        // The suit is shielded, EMP barely reaches the salvager inside
+        .with(Resistances {
+            kinetic: 0,
+            energy: 0,
+            thermal: 10,
+            emp: 90,
+        })
+        .with(BaseStats {
+            hp: 30,
+            energy: 30,
+            oxygen: 100,
+            stamina: 20,
+            defense: 2,
+            view_range: 8,
+        })
         .marked::<SimpleMarker<SerializeMe>>()
         .build()
 }
 
-pub fn random_robot(ecs: &mut World, x: i32, y: i32) {
-    let roll: i32;
+fn random_hostile(ecs: &mut World, x: i32, y: i32, depth: i32, room_type: RoomType) {
+    let name: String;
     {
         let mut rng = ecs.write_resource::<RandomNumberGenerator>();
-        roll = rng.roll_dice(1, 2);
-    }
-    match roll {
-        1 => robot(ecs, x, y),
-        _ => minin_robot(ecs, x, y),
+        let raws = ecs.fetch::<RawMaster>();
+        name = raws.hostile_table(depth, Some(room_type)).roll(&mut rng);
     }
+    spawn_named_robot(ecs, &name, x, y, depth);
 }
 
-fn robot(ecs: &mut World, x: i32, y: i32) {
-    mob(ecs, x, y, rltk::to_cp437('R'), "Robot");
+// What a fabricator can turn out
+pub fn random_machine(ecs: &mut World, x: i32, y: i32, depth: i32) -> Option<Entity> {
+    let name: String;
+    {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let raws = ecs.fetch::<RawMaster>();
+        name = raws.build_table().roll(&mut rng);
+    }
+    spawn_named_robot(ecs, &name, x, y, depth)
 }
 
-fn minin_robot(ecs: &mut World, x: i32, y: i32) {
-    mob(ecs, x, y, rltk::to_cp437('m'), "Miningrobot");
-}
+// Builds a robot, or anything else that fights, from its raws entry
+pub fn spawn_named_robot(ecs: &mut World, name: &str, x: i32, y: i32, depth: i32) -> Option<Entity> {
+    let raw = ecs.fetch::<RawMaster>().robot(name).cloned()?;
+    let patrol_route = if raw.brain { patrol_route(ecs, x, y) } else { Vec::new() };
+    let hp = raw.stats.hp + (depth - 1) * raw.stats.hp_per_depth;
+    let energy = raw.gun.as_ref().map_or(0, |gun| gun.energy);
 
-fn mob<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: rltk::FontCharType, name: S) {
-    ecs.create_entity()
+    let mut builder = ecs.create_entity()
         .with(Position { x, y })
-        .with(Renderable {
-            glyph,
-            fg: RGB::named(rltk::BLUE),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 1,
-        })
+        .with(renderable(&raw.renderable))
         .with(Viewshed {
             visible_tiles: Vec::new(),
-            range: 8,
+            range: raw.view_range,
             dirty: true,
         })
-        .with(Robot {})
         .with(Name {
-            name: name.to_string(),
+            name: raw.name.clone(),
         })
         .with(BlocksTile {})
+        .with(Faction {
+            id: raw.faction,
+        })
+        .with(Resistances {
+            kinetic: raw.resistances.kinetic,
+            energy: raw.resistances.energy,
+            thermal: raw.resistances.thermal,
+            emp: raw.resistances.emp,
+        })
         .with(CombatStats {
             hp: Pool {
-                current: 16,
-                max: 16,
+                current: hp,
+                max: hp,
                 name: "Health".to_string(),
             },
             energy: Pool {
-                current: 0,
-                max: 0,
+                current: energy,
+                max: energy,
                 name: "Energy".to_string(),
             },
             oxygen: Pool {
@@ -162,205 +293,289 @@ fn mob<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: rltk::FontCharType,
                 max: 0,
                 name: "Oxygen".to_string(),
             },
-            defense: 1,
-            power: 4,
-        })
-        .marked::<SimpleMarker<SerializeMe>>()
-        .build();
-}
+            stamina: Pool {
+                current: 0,
+                max: 0,
+                name: "Stamina".to_string(),
+            },
+            heat: Pool {
+                current: 0,
+                max: 0,
+                name: "Suit heat".to_string(),
+            },
+            defense: raw.stats.defense,
+            evasion: raw.stats.evasion,
+            power: raw.stats.power + (depth - 1) * raw.stats.power_per_depth,
+        });
 
-fn random_item(ecs: &mut World, x: i32, y: i32) {
-    let roll: i32;
-    {
-        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
-        roll = rng.roll_dice(1, 6);
+    if raw.robot {
+        builder = builder.with(Robot {});
     }
-    match roll {
-        1 => stim_packs(ecs, x, y),
-        2 => laser_torch(ecs, x, y),
-        3 => emp_bombs(ecs, x, y),
-        4 => oxygen_canister(ecs, x, y),
-        5 => battery(ecs, x, y),
-        _ => grenades(ecs, x, y),
+    if raw.brain {
+        builder = builder.with(Brain {
+            state: AiState::Patrol,
+            patrol_route,
+            next_waypoint: 0,
+            last_seen: None,
+            search_turns: 0,
+        });
+    }
+    if let Some(speed) = raw.speed {
+        // Starts a turn behind so it doesn't get a free action the moment it appears
+        builder = builder.with(Initiative { speed, energy: -TURN_COST });
+    }
+    if raw.opens_doors {
+        builder = builder.with(CanOpenDoors {});
+    }
+    if raw.immobile {
+        builder = builder.with(Immobile {});
+    }
+    if let Some(gun) = &raw.gun {
+        builder = builder.with(RangedWeapon {
+            range: gun.range,
+            damage: gun.damage + (depth - 1) * gun.damage_per_depth,
+            accuracy: gun.accuracy,
+            damage_type: gun.damage_type,
+            pattern: FiringPattern::Single,
+        });
+    }
+    if raw.security_camera {
+        builder = builder.with(SecurityCamera {});
+    }
+    if let Some(fabricator) = &raw.fabricator {
+        let interval = i32::max(fabricator.min_interval, fabricator.interval - (depth - 1) * fabricator.faster_per_depth);
+        builder = builder.with(Fabricator { interval, progress: 0 });
+    }
+    if let Some(loot) = &raw.loot {
+        builder = builder.with(LootTable { table: loot.clone() });
     }
-}
 
-pub fn stim_packs(ecs: &mut World, x: i32, y: i32) {
-    ecs.create_entity()
-        .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437('¡'),
-            fg: RGB::named(rltk::MAGENTA),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
-        .with(Name {
-            name: "Basic Stim Pack".to_string(),
-        })
-        .with(Item {})
-        .with(Consumable {})
-        .with(ProvidesHealing { heal_amount: 8 })
-        .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+    Some(builder.marked::<SimpleMarker<SerializeMe>>().build())
 }
 
-fn grenades(ecs: &mut World, x: i32, y: i32) {
-    ecs.create_entity()
-        .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437('g'),
-            fg: RGB::named(rltk::DARK_OLIVE),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
-        .with(Name {
-            name: "Grenade".to_string(),
-        })
-        .with(Item {})
-        .with(Consumable {})
-        .with(Ranged { range: 6 })
-        .with(InflictsDamage { damage: 8 })
-        .with(AreaOfEffect { radius: 3 })
-        .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+// Starts where the hostile was placed and loops through a floor tile in each of a few random rooms
+fn patrol_route(ecs: &mut World, x: i32, y: i32) -> Vec<Point> {
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let map = ecs.fetch::<Map>();
+    let mut route = vec![Point::new(x, y)];
+    if map.rooms.is_empty() {
+        return route;
+    }
+    for _i in 0..PATROL_STOPS {
+        let room = map.rooms[rng.range(0, map.rooms.len())];
+        if let Some(stop) = random_floor_tile(&mut rng, &map, &room, &[]) {
+            route.push(stop);
+        }
+    }
+    route
 }
 
-fn laser_torch(ecs: &mut World, x: i32, y: i32) {
-    ecs.create_entity()
-        .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437('t'),
-            fg: RGB::named(rltk::LIGHTYELLOW1),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
-        .with(Name {
-            name: "Laser torch".to_string(),
-        })
-        .with(Item {})
-        .with(MeleePowerBonus { power: 4 })
-        .marked::<SimpleMarker<SerializeMe>>()
-        .with(Equippable {
-            slot: EquipmentSlot::Weapon,
-        })
-        .build();
+
+fn random_item(ecs: &mut World, x: i32, y: i32, depth: i32, room_type: RoomType) {
+    let name: String;
+    {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let raws = ecs.fetch::<RawMaster>();
+        name = raws.item_table(depth, Some(room_type)).roll(&mut rng);
+    }
+    spawn_named_item(ecs, &name, x, y);
 }
 
-pub fn blaster(ecs: &mut World, x: i32, y: i32) {
-    ecs.create_entity()
+// Builds an item from its raws entry, it only gets the components the entry lists
+pub fn spawn_named_item(ecs: &mut World, name: &str, x: i32, y: i32) -> Option<Entity> {
+    let raw = ecs.fetch::<RawMaster>().item(name).cloned()?;
+
+    let mut builder = ecs.create_entity()
         .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437('t'),
-            fg: RGB::named(rltk::LIGHTYELLOW1),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
+        .with(renderable(&raw.renderable))
         .with(Name {
-            name: "Blaster".to_string(),
-        })
-        .with(Item {})
-        .with(RangedWeapon {
-            range: 4,
-            damage: 4,
+            name: raw.name.clone(),
         })
-        .marked::<SimpleMarker<SerializeMe>>()
-        .with(Equippable {
-            slot: EquipmentSlot::Weapon,
-        })
-        .build();
+        .with(Item {});
+
+    if raw.consumable {
+        builder = builder.with(Consumable {});
+    }
+    if let Some(range) = raw.ranged {
+        builder = builder.with(Ranged { range });
+    }
+    if let Some(heal_amount) = raw.healing {
+        builder = builder.with(ProvidesHealing { heal_amount });
+    }
+    if let Some(oxygen_amount) = raw.oxygen {
+        builder = builder.with(ProvidesOxygen { oxygen_amount });
+    }
+    if let Some(energy_amount) = raw.energy {
+        builder = builder.with(ProvidesEnergy { energy_amount });
+    }
+    if let Some(output) = raw.power_output {
+        builder = builder.with(PowerCore { output });
+    }
+    if let Some(damage) = &raw.damage {
+        builder = builder.with(InflictsDamage { damage: damage.amount, damage_type: damage.damage_type });
+    }
+    if let Some(radius) = raw.area_of_effect {
+        builder = builder.with(AreaOfEffect { radius });
+    }
+    if let Some(turns) = raw.confusion {
+        builder = builder.with(Confusion { turns });
+    }
+    if let Some(bonus) = raw.hacking_bonus {
+        builder = builder.with(HackingTool { bonus });
+    }
+    if raw.ammo_pack {
+        builder = builder.with(AmmoPack {});
+    }
+    if raw.artefact {
+        builder = builder.with(ArtefactFromYendoria {});
+    }
+    if let Some(slot) = raw.equippable {
+        builder = builder.with(Equippable { slot });
+    }
+    if let Some(melee) = &raw.melee_bonus {
+        builder = builder.with(MeleePowerBonus { power: melee.power, accuracy: melee.accuracy, damage_type: melee.damage_type });
+    }
+    if let Some(weapon) = &raw.ranged_weapon {
+        builder = builder
+            .with(RangedWeapon {
+                range: weapon.range,
+                damage: weapon.damage,
+                accuracy: weapon.accuracy,
+                damage_type: weapon.damage_type,
+                pattern: weapon.pattern,
+            })
+            .with(Magazine {
+                current: weapon.magazine,
+                capacity: weapon.magazine,
+                ammo: weapon.ammo,
+            });
+    }
+    if let Some(defense) = raw.defense_bonus {
+        builder = builder.with(DefenseBonus { defense });
+    }
+    if let Some(range) = raw.view_range_bonus {
+        builder = builder.with(ViewRangeBonus { range });
+    }
+    if let Some(bonus) = &raw.pool_bonus {
+        builder = builder.with(PoolBonus { hp: bonus.hp, energy: bonus.energy, oxygen: bonus.oxygen });
+    }
+    if let Some(percent) = raw.oxygen_efficiency {
+        builder = builder.with(OxygenEfficiency { percent });
+    }
+    if let Some(amount) = raw.thermal_regulator {
+        builder = builder.with(ThermalRegulator { amount });
+    }
+
+    Some(builder.marked::<SimpleMarker<SerializeMe>>().build())
 }
 
-fn emp_bombs(ecs: &mut World, x: i32, y: i32) {
-    ecs.create_entity()
+pub fn spawn_named_prop(ecs: &mut World, name: &str, x: i32, y: i32) -> Option<Entity> {
+    let raw = ecs.fetch::<RawMaster>().prop(name).cloned()?;
+    Some(ecs.create_entity()
         .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437(')'),
-            fg: RGB::named(rltk::PINK),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
+        .with(renderable(&raw.renderable))
         .with(Name {
-            name: "EMP Bomb".to_string(),
+            name: raw.name.clone(),
         })
-        .with(Item {})
-        .with(Consumable {})
-        .with(Ranged { range: 6 })
-        .with(Confusion { turns: 4 })
         .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+        .build())
 }
 
-fn oxygen_canister(ecs: &mut World, x: i32, y: i32) {
-    ecs.create_entity()
-        .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437('o'),
-            fg: RGB::named(rltk::AQUA),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
-        .with(Name {
-            name: "Oxygen Canister".to_string(),
-        })
-        .with(Item {})
-        .with(Consumable {})
-        .with(ProvidesOxygen{oxygen_amount: 10})
-        .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+// The raws were checked at startup, so the glyph and colours are known to be good
+fn renderable(raw: &RenderableRaw) -> Renderable {
+    Renderable {
+        glyph: rltk::to_cp437(raw.glyph.chars().next().unwrap_or('?')),
+        fg: RGB::from_hex(&raw.fg).unwrap_or_else(|_| RGB::named(rltk::WHITE)),
+        bg: RGB::from_hex(&raw.bg).unwrap_or_else(|_| RGB::named(rltk::BLACK)),
+        render_order: raw.render_order,
+    }
 }
 
-pub fn oxygen_tank(ecs: &mut World, x: i32, y: i32) {
-    ecs.create_entity()
-        .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437('O'),
-            fg: RGB::named(rltk::AQUA),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
-        .with(Name {
-            name: "Oxygen Tank".to_string(),
-        })
-        .with(Item {})
-        .with(Consumable {})
-        .with(ProvidesOxygen{oxygen_amount: 50})
-        .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+// Cameras watch some of the rooms and a single fabricator waits somewhere on the deck
+pub fn spawn_security(ecs: &mut World, rooms: &[Rect], depth: i32) {
+    let mut camera_spawn_points: Vec<Point> = Vec::new();
+    let mut fabricator_spawn_point: Option<Point> = None;
+
+    // Scope to keep the borrow checker happy
+    {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let map = ecs.fetch::<Map>();
+        // The rooms are already populated, so stay clear of whatever stands there
+        let mut taken = occupied_tiles(ecs);
+
+        // The docking room is left unwatched
+        for room in rooms.iter().skip(1) {
+            if rng.roll_dice(1, 100) > CAMERA_CHANCE { continue; }
+            if let Some(spot) = random_floor_tile(&mut rng, &map, room, &taken) {
+                camera_spawn_points.push(spot);
+                taken.push(spot);
+            }
+        }
+
+        if rooms.len() > 1 {
+            let room = rooms[rng.roll_dice(1, rooms.len() as i32 - 1) as usize];
+            fabricator_spawn_point = random_floor_tile(&mut rng, &map, &room, &taken);
+        }
+    }
+
+    for spot in camera_spawn_points.iter() {
+        spawn_named_robot(ecs, "Security camera", spot.x, spot.y, depth);
+    }
+    if let Some(spot) = fabricator_spawn_point {
+        spawn_named_robot(ecs, "Fabricator", spot.x, spot.y, depth);
+    }
 }
 
-pub fn battery(ecs: &mut World, x: i32, y: i32) {
+
+// What's left of a destroyed robot, it can be stripped once for parts
+pub fn wreck(ecs: &mut World, x: i32, y: i32, robot_name: &str, table: &str) {
     ecs.create_entity()
         .with(Position { x, y })
         .with(Renderable {
-            glyph: rltk::to_cp437('b'),
-            fg: RGB::named(rltk::YELLOW),
+            glyph: rltk::to_cp437('%'),
+            fg: RGB::named(rltk::GRAY),
             bg: RGB::named(rltk::BLACK),
-            render_order: 2,
+            render_order: 3,
         })
         .with(Name {
-            name: "Battery".to_string(),
+            name: format!("Wreck of {}", robot_name),
+        })
+        .with(Wreckage {
+            table: table.to_string(),
         })
-        .with(Item {})
-        .with(Consumable {})
-        .with(ProvidesEnergy{energy_amount: 10})
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
 
-pub fn artefact(ecs: &mut World, x: i32, y: i32){
-    ecs.create_entity()
-        .with(Position { x, y })
-        .with(Renderable {
-            glyph: rltk::to_cp437('Y'),
-            fg: RGB::named(rltk::GOLD),
-            bg: RGB::named(rltk::BLACK),
-            render_order: 2,
-        })
-        .with(Name {
-            name: "Artefact from Yendoria".to_string(),
-        })
-        .with(Item {})
-        .marked::<SimpleMarker<SerializeMe>>()
-        .with(ArtefactFromYendoria{})
-        .build();
+// Rolls the wreck's loot table and drops whatever turns up on its tile, returns the names of the drops
+pub fn salvage_wreck(ecs: &mut World, wreck: Entity) -> Vec<String> {
+    let (x, y, table) = {
+        let positions = ecs.read_storage::<Position>();
+        let wreckage = ecs.read_storage::<Wreckage>();
+        match (positions.get(wreck), wreckage.get(wreck)) {
+            (Some(pos), Some(wreckage)) => (pos.x, pos.y, wreckage.table.clone()),
+            _ => return Vec::new(),
+        }
+    };
+
+    let mut drops: Vec<String> = Vec::new();
+    {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let loot = ecs.fetch::<RawMaster>().loot_table(&table);
+        for _i in 0..SALVAGE_ROLLS {
+            let drop = loot.roll(&mut rng);
+            if drop != "None" {
+                drops.push(drop);
+            }
+        }
+    }
+    for drop in drops.iter() {
+        spawn_named_item(ecs, drop, x, y);
+    }
+
+    ecs.write_storage::<Wreckage>().remove(wreck);
+    if let Some(name) = ecs.write_storage::<Name>().get_mut(wreck) {
+        name.name = "Stripped wreck".to_string();
+    }
+    drops
 }
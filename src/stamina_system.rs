@@ -0,0 +1,48 @@
+use specs::prelude::*;
+use super::{CombatStats, RunState};
+use crate::{BreathOxygen, Exertion, WantsToRest};
+use std::cmp::{max, min};
+
+pub const MELEE_STAMINA_COST : i32 = 2;
+pub const SPRINT_STAMINA_COST : i32 = 6;
+const REST_RECOVERY : i32 = 4;
+const IDLE_RECOVERY : i32 = 1;
+
+pub struct StaminaSystem {}
+
+impl<'a> System<'a> for StaminaSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( Entities<'a>,
+                        WriteStorage<'a, CombatStats>,
+                        WriteStorage<'a, Exertion>,
+                        WriteStorage<'a, WantsToRest>,
+                        WriteStorage<'a, BreathOxygen>,
+                        ReadExpect<'a, RunState> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, mut stats, mut exertions, mut resting, mut breaths, runstate) = data;
+
+        if *runstate == RunState::PlayerTurn {
+            for (entity, stats) in (&entities, &mut stats).join() {
+                if stats.stamina.max <= 0 { continue; }
+
+                // Catching your breath is quicker when standing still than when walking about
+                if let Some(exertion) = exertions.get(entity) {
+                    stats.stamina.current = max(0, stats.stamina.current - exertion.amount.iter().sum::<i32>());
+                } else if resting.get(entity).is_some() {
+                    stats.stamina.current = min(stats.stamina.max, stats.stamina.current + REST_RECOVERY);
+                } else {
+                    stats.stamina.current = min(stats.stamina.max, stats.stamina.current + IDLE_RECOVERY);
+                }
+
+                // A winded salvager gulps down extra suit air
+                if stats.stamina.current <= stats.stamina.max / 4 {
+                    BreathOxygen::new_breath(&mut breaths, entity, 1);
+                }
+            }
+        }
+
+        exertions.clear();
+        resting.clear();
+    }
+}
@@ -0,0 +1,72 @@
+use specs::prelude::*;
+use super::{gamelog::GameLog, CombatStats, Map, Player, Position, SufferDamage};
+use crate::initiative_system::GameClock;
+use crate::{DamageType, Equipped, ThermalRegulator};
+use std::cmp::{max, min};
+
+// Ambient temperatures outside this band work on the suit's own heat
+const HOT_LIMIT : i32 = 40;
+const COLD_LIMIT : i32 = 0;
+
+pub struct SuitHeatSystem {}
+
+impl<'a> System<'a> for SuitHeatSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = ( Entities<'a>,
+                        WriteExpect<'a, GameLog>,
+                        WriteStorage<'a, CombatStats>,
+                        WriteStorage<'a, SufferDamage>,
+                        ReadExpect<'a, Map>,
+                        ReadStorage<'a, Position>,
+                        ReadStorage<'a, Player>,
+                        ReadStorage<'a, Equipped>,
+                        ReadStorage<'a, ThermalRegulator>,
+                        ReadExpect<'a, GameClock> );
+
+    fn run(&mut self, data : Self::SystemData) {
+        let (entities, mut log, mut stats, mut damages, map, positions, players, equipped, regulators, clock) = data;
+
+        if !clock.ticked { return }
+
+        for (entity, stats, pos) in (&entities, &mut stats, &positions).join() {
+            if stats.heat.max <= 0 { continue; }
+            let comfort = stats.heat.max / 2;
+            let ambient = map.temperature[map.xy_idx(pos.x, pos.y)];
+
+            // The hotter or colder it gets, the faster the suit follows
+            let mut change = 0;
+            if ambient > HOT_LIMIT {
+                change = 1 + (ambient - HOT_LIMIT) / 40;
+            } else if ambient < COLD_LIMIT {
+                change = -1 - (COLD_LIMIT - ambient) / 20;
+            } else if stats.heat.current > comfort {
+                change = -1;
+            } else if stats.heat.current < comfort {
+                change = 1;
+            }
+
+            let mut regulation = 0;
+            for (equipped_by, regulator) in (&equipped, &regulators).join() {
+                if equipped_by.owner == entity {
+                    regulation += regulator.amount;
+                }
+            }
+            if change > 0 {
+                change = max(0, change - regulation);
+            } else if change < 0 {
+                change = min(0, change + regulation);
+            }
+
+            stats.heat.current = max(0, min(stats.heat.max, stats.heat.current + change));
+
+            let is_player = players.get(entity).is_some();
+            if stats.heat.current == stats.heat.max {
+                SufferDamage::new_damage(&mut damages, entity, 1, DamageType::Thermal);
+                if is_player { log.entries.push("Your suit is overheating!".to_string()); }
+            } else if stats.heat.current == 0 {
+                SufferDamage::new_damage(&mut damages, entity, 1, DamageType::Kinetic);
+                if is_player { log.entries.push("You are freezing in your suit!".to_string()); }
+            }
+        }
+    }
+}
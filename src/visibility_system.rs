@@ -2,6 +2,9 @@ use specs::prelude::*;
 use super::{Viewshed, Position, Map, Player};
 use rltk::{field_of_view, Point};
 
+// Without ship lighting the salvager only sees as far as the helmet lamp reaches
+const HELMET_LAMP_RANGE : i32 = 4;
+
 pub struct VisibilitySystem {}
 
 impl<'a> System<'a> for VisibilitySystem {
@@ -18,7 +21,11 @@ impl<'a> System<'a> for VisibilitySystem {
             if _viewshed.dirty {
                 _viewshed.dirty = false;
                 _viewshed.visible_tiles.clear();
-                _viewshed.visible_tiles = field_of_view(Point::new(_pos.x, _pos.y), _viewshed.range, &*map);
+                let mut range = _viewshed.range;
+                if player.get(_entitiy).is_some() && !map.power.lighting {
+                    range = i32::min(range, HELMET_LAMP_RANGE);
+                }
+                _viewshed.visible_tiles = field_of_view(Point::new(_pos.x, _pos.y), range, &*map);
                 _viewshed.visible_tiles.retain(|p| p.x >= 0 && p.x < map.width && p.y >= 0 && p.y < map.height );
 
                 let _player : Option<&Player> = player.get(_entitiy);